@@ -0,0 +1,79 @@
+//! The flexible deserializers against plain serde. Run with `cargo bench -p serde-flexible`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde::de::value::{Error, StrDeserializer};
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
+use serde_flexible::config::{DefaultConfig, FlexConfig};
+use serde_flexible::{as_bool, as_f64, as_i64, as_u64, as_u64_config, as_u64_opt, with_config};
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Plain {
+    id: u64,
+    delta: i64,
+    price: f64,
+    active: bool,
+    count: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Flexible {
+    #[serde(deserialize_with = "as_u64")]
+    id: u64,
+    #[serde(deserialize_with = "as_i64")]
+    delta: i64,
+    #[serde(deserialize_with = "as_f64")]
+    price: f64,
+    #[serde(deserialize_with = "as_bool")]
+    active: bool,
+    #[serde(deserialize_with = "as_u64_opt")]
+    count: Option<u64>,
+}
+
+fn records(row: &str) -> String {
+    format!("[{}]", vec![row; 1000].join(","))
+}
+
+/// Whole documents: plain serde against the flexible deserializers on the same native input,
+/// and on strings. `scoped` reads the configuration of `with_config` for every field.
+fn documents(c: &mut Criterion) {
+    let native = records(r#"{"id": 12345678, "delta": -42, "price": 19.99, "active": true, "count": null}"#);
+    let strings = records(r#"{"id": "12345678", "delta": "-42", "price": "19.99", "active": "Yes", "count": "unknown"}"#);
+
+    let mut group = c.benchmark_group("documents");
+    group.bench_function("plain/native", |b| b.iter(|| serde_json::from_str::<Vec<Plain>>(black_box(&native)).unwrap()));
+    group.bench_function("flexible/native", |b| b.iter(|| serde_json::from_str::<Vec<Flexible>>(black_box(&native)).unwrap()));
+    group.bench_function("flexible/strings", |b| b.iter(|| serde_json::from_str::<Vec<Flexible>>(black_box(&strings)).unwrap()));
+    group.bench_function("flexible/native/scoped", |b| {
+        b.iter(|| with_config(FlexConfig::DEFAULT, || serde_json::from_str::<Vec<Flexible>>(black_box(&native)).unwrap()))
+    });
+    group.finish();
+}
+
+fn str_de(v: &str) -> StrDeserializer<'_, Error> {
+    v.into_deserializer()
+}
+
+/// Single strings, by shape. `as_u64_config` doesn't look up the global configuration.
+fn strings(c: &mut Criterion) {
+    let mut group = c.benchmark_group("strings");
+    for v in ["12345678", "18446744073709551615", "1234.5", "1.5e3", "abc"] {
+        group.bench_with_input(BenchmarkId::new("as_u64", v), v, |b, v| b.iter(|| as_u64(str_de(black_box(v)))));
+        group.bench_with_input(BenchmarkId::new("as_u64_config", v), v, |b, v| b.iter(|| as_u64_config::<DefaultConfig, _>(str_de(black_box(v)))));
+        group.bench_with_input(BenchmarkId::new("as_i64", v), v, |b, v| b.iter(|| as_i64(str_de(black_box(v)))));
+        group.bench_with_input(BenchmarkId::new("as_f64", v), v, |b, v| b.iter(|| as_f64(str_de(black_box(v)))));
+        group.bench_with_input(BenchmarkId::new("str::parse", v), v, |b, v| b.iter(|| black_box(v).parse::<u64>()));
+    }
+    for v in ["true", "Yes", "OFF", "maybe"] {
+        group.bench_with_input(BenchmarkId::new("as_bool", v), v, |b, v| b.iter(|| as_bool(str_de(black_box(v)))));
+    }
+    for v in ["Unknown", "42"] {
+        group.bench_with_input(BenchmarkId::new("as_u64_opt", v), v, |b, v| b.iter(|| as_u64_opt(str_de(black_box(v)))));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, documents, strings);
+criterion_main!(benches);
@@ -0,0 +1,409 @@
+//! Coercion rules of the `bool`, `char`, `f64`, `i64` and `u64` deserializers.
+//!
+//! The rules are described by a [`FlexConfig`], built with `const` methods. [`FlexConfig::new`]
+//! is the behavior documented for each deserializer. The default functions (`as_u64`,
+//! `as_bool_opt`, `as_f64_lenient`, ...) use the global configuration, which starts as
+//! [`FlexConfig::DEFAULT`] and can be replaced with [`set_config`]. The `_config` variants
+//! (`as_u64_config::<C, _>`, `as_u64_opt_config::<C, _>`, ...) take it from a [`Config`]
+//! type instead, so it can be chosen per field. Without the `std` feature there is no global
+//! configuration, the default functions always use [`FlexConfig::DEFAULT`].
+//!
+//! [`with_config`] replaces the global configuration for the current thread while a closure runs,
+//! e.g. to be strict for one request and lenient for another:
+//!
+//! ```
+//! use serde_flexible::config::FlexConfig;
+//! use serde_flexible::{with_config, Flexible};
+//!
+//! let strict = FlexConfig::new().strict(true);
+//! assert!(with_config(strict, || serde_json::from_str::<Flexible<u64>>(r#""1""#)).is_err());
+//! assert!(serde_json::from_str::<Flexible<u64>>(r#""1""#).is_ok());
+//! ```
+//!
+//! A `_config` variant with a per-field type:
+//!
+//! ```
+//! use serde::Deserialize;
+//! use serde_flexible::as_u64_config;
+//! use serde_flexible::config::{Config, FlexConfig, Rounding};
+//!
+//! struct Exact;
+//!
+//! impl Config for Exact {
+//!     fn config() -> FlexConfig { FlexConfig::new().rounding(Rounding::Reject).trim(true) }
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Row {
+//!     #[serde(deserialize_with = "as_u64_config::<Exact, _>")]
+//!     count: u64,
+//! }
+//!
+//! assert_eq!(serde_json::from_str::<Row>(r#"{"count": " 2.0 "}"#).unwrap().count, 2);
+//! assert!(serde_json::from_str::<Row>(r#"{"count": 2.5}"#).is_err());
+//! ```
+
+use crate::float;
+use crate::sentinels::null_sentinels;
+#[cfg(feature = "std")]
+use std::cell::RefCell;
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+/// The strings `as_bool` reads as `true` by default, matched case insensitively.
+pub const DEFAULT_TRUE_WORDS: &[&str] = &["true", "yes", "on", "y", "t", "1", "ok"];
+
+/// The strings `as_bool` reads as `false` by default, matched case insensitively.
+pub const DEFAULT_FALSE_WORDS: &[&str] = &["false", "no", "off", "n", "f", "0"];
+
+#[cfg(feature = "std")]
+static GLOBAL_CONFIG: RwLock<FlexConfig> = RwLock::new(FlexConfig::DEFAULT);
+
+/// Whether `set_config` was called, until then `GLOBAL_CONFIG` is not read.
+#[cfg(feature = "std")]
+static GLOBAL_CONFIG_SET: AtomicBool = AtomicBool::new(false);
+
+/// The number of running `with_config` calls on all threads, `SCOPED_CONFIGS` is not read without them.
+#[cfg(feature = "std")]
+static SCOPED_CONFIG_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "std")]
+thread_local! {
+    static SCOPED_CONFIGS: RefCell<Vec<FlexConfig>> = const { RefCell::new(Vec::new()) };
+}
+
+/// How the integer deserializers convert floats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Rounding {
+    /// To the nearest integer, halfway cases away from zero.
+    #[default]
+    Nearest,
+    /// Towards negative infinity.
+    Floor,
+    /// Towards positive infinity.
+    Ceil,
+    /// Towards zero.
+    Trunc,
+    /// Floats with a fractional part are an error.
+    Reject,
+}
+
+/// Whether the deserializers ask the format for a value of their type instead of calling
+/// `deserialize_any`.
+///
+/// **Formats that are not self-describing, like bincode and postcard, need [`TypeHints::Always`].**
+/// They cannot deserialize without knowing the type, so the deserializers call `deserialize_u64`,
+/// `deserialize_bool`, `deserialize_option` and so on instead. The value is then read as it was
+/// serialized, without coercions. Self-describing formats, including binary ones like
+/// MessagePack, keep the default [`TypeHints::Never`], so numbers in strings and bytes are still
+/// coerced.
+///
+/// The serializers don't depend on this option. The `string` modules, e.g. `u64::string`, write
+/// strings and read them back with `deserialize_str` under `Always`. `js_safe` and
+/// [`Shaped`](crate::Shaped) write a number or a string depending on the value, so only
+/// self-describing formats can read them back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TypeHints {
+    /// Always `deserialize_any`.
+    #[default]
+    Never,
+    /// Always type hints, for formats that are not self-describing.
+    Always,
+}
+
+/// Coercion rules, see the [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FlexConfig {
+    accept_floats: bool,
+    accept_strings: bool,
+    rounding: Rounding,
+    trim: bool,
+    sentinels: Option<&'static [&'static str]>,
+    true_words: &'static [&'static str],
+    false_words: &'static [&'static str],
+    strict: bool,
+    type_hints: TypeHints,
+}
+
+impl FlexConfig {
+    /// The documented behavior of the deserializers.
+    pub const DEFAULT: FlexConfig = FlexConfig::new();
+
+    pub const fn new() -> Self {
+        FlexConfig {
+            accept_floats: true,
+            accept_strings: true,
+            rounding: Rounding::Nearest,
+            trim: false,
+            sentinels: None,
+            true_words: DEFAULT_TRUE_WORDS,
+            false_words: DEFAULT_FALSE_WORDS,
+            strict: false,
+            type_hints: TypeHints::Never,
+        }
+    }
+
+    /// Whether the integer deserializers accept floats and float strings like `"1.5"`. Default `true`.
+    pub const fn accept_floats(mut self, accept: bool) -> Self {
+        self.accept_floats = accept;
+        self
+    }
+
+    /// Whether numbers and bools may be written as strings, like `"42"` or `"yes"`. Default `true`.
+    pub const fn accept_strings(mut self, accept: bool) -> Self {
+        self.accept_strings = accept;
+        self
+    }
+
+    /// How the integer deserializers convert floats. Default [`Rounding::Nearest`].
+    pub const fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Whether surrounding whitespace is trimmed from number and bool strings. Default `false`.
+    pub const fn trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// The null sentinels of the optional deserializers. By default the global set of
+    /// [`sentinels`](crate::sentinels) is used. The `_opt_with` variants always use their own.
+    pub const fn null_sentinels(mut self, sentinels: &'static [&'static str]) -> Self {
+        self.sentinels = Some(sentinels);
+        self
+    }
+
+    /// The strings `as_bool` reads as `true` and `false`, matched case insensitively.
+    /// Default [`DEFAULT_TRUE_WORDS`] and [`DEFAULT_FALSE_WORDS`].
+    pub const fn bool_words(mut self, true_words: &'static [&'static str], false_words: &'static [&'static str]) -> Self {
+        self.true_words = true_words;
+        self.false_words = false_words;
+        self
+    }
+
+    /// Only accept values that already have the target type: integers for `i64` and `u64`,
+    /// numbers for `f64`, bools for `bool` and characters or one-character strings for `char`.
+    /// Null sentinels still apply. Default `false`.
+    pub const fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// When the deserializers use type hints instead of `deserialize_any`. Default [`TypeHints::Never`].
+    pub const fn type_hints(mut self, type_hints: TypeHints) -> Self {
+        self.type_hints = type_hints;
+        self
+    }
+
+    pub(crate) fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    pub(crate) fn floats(&self) -> bool {
+        self.accept_floats && !self.strict
+    }
+
+    pub(crate) fn strings(&self) -> bool {
+        self.accept_strings && !self.strict
+    }
+
+    pub(crate) fn trimmed<'a>(&self, v: &'a str) -> &'a str {
+        if self.trim { v.trim() } else { v }
+    }
+
+    /// Rounds `v` to an integral value, `None` if it has to be rejected.
+    pub(crate) fn round(&self, v: f64) -> Option<f64> {
+        match self.rounding {
+            Rounding::Nearest => Some(float::round(v)),
+            Rounding::Floor => Some(float::floor(v)),
+            Rounding::Ceil => Some(float::ceil(v)),
+            Rounding::Trunc => Some(float::trunc(v)),
+            Rounding::Reject if float::trunc(v) == v => Some(v),
+            Rounding::Reject => None,
+        }
+    }
+
+    pub(crate) fn hinted(&self) -> bool {
+        self.type_hints == TypeHints::Always
+    }
+
+    pub(crate) fn sentinels(&self) -> &'static [&'static str] {
+        self.sentinels.unwrap_or_else(null_sentinels)
+    }
+
+    pub(crate) fn bool_word(&self, v: &str) -> Option<bool> {
+        if self.true_words.iter().any(|w| w.eq_ignore_ascii_case(v)) {
+            Some(true)
+        } else if self.false_words.iter().any(|w| w.eq_ignore_ascii_case(v)) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn has_default_bool_words(&self) -> bool {
+        self.true_words == DEFAULT_TRUE_WORDS && self.false_words == DEFAULT_FALSE_WORDS
+    }
+
+    pub(crate) fn true_words(&self) -> &'static [&'static str] {
+        self.true_words
+    }
+
+    pub(crate) fn false_words(&self) -> &'static [&'static str] {
+        self.false_words
+    }
+}
+
+impl Default for FlexConfig {
+    fn default() -> Self {
+        FlexConfig::DEFAULT
+    }
+}
+
+/// A source of coercion rules for the `_config` deserializers and [`as_num`](crate::as_num).
+///
+/// Implemented by zero-sized policy types, the rules are picked at compile time per field.
+pub trait Config {
+    fn config() -> FlexConfig;
+}
+
+/// The global configuration, see [`set_config`], or the innermost [`with_config`] on this thread.
+pub struct GlobalConfig;
+
+/// Always [`FlexConfig::DEFAULT`], regardless of the global configuration.
+pub struct DefaultConfig;
+
+/// [`FlexConfig::DEFAULT`] with [`strict`](FlexConfig::strict) set.
+pub struct Strict;
+
+/// [`FlexConfig::DEFAULT`] with [`trim`](FlexConfig::trim) set.
+pub struct Lenient;
+
+impl Config for GlobalConfig {
+    fn config() -> FlexConfig { config() }
+}
+
+impl Config for DefaultConfig {
+    fn config() -> FlexConfig { FlexConfig::DEFAULT }
+}
+
+impl Config for Strict {
+    fn config() -> FlexConfig { FlexConfig::new().strict(true) }
+}
+
+impl Config for Lenient {
+    fn config() -> FlexConfig { FlexConfig::new().trim(true) }
+}
+
+/// Replaces the global configuration used by the default deserializers.
+#[cfg(feature = "std")]
+pub fn set_config(config: FlexConfig) {
+    *GLOBAL_CONFIG.write().unwrap_or_else(|err| err.into_inner()) = config;
+    GLOBAL_CONFIG_SET.store(true, Ordering::Release);
+}
+
+/// Returns the configuration of the innermost [`with_config`] on this thread, or the global one.
+#[cfg(feature = "std")]
+pub fn config() -> FlexConfig {
+    // the default deserializers call this for every field, so skip the lock and the thread local
+    // while the configuration was never changed
+    let scoped = match SCOPED_CONFIG_COUNT.load(Ordering::Relaxed) {
+        0 => None,
+        _ => SCOPED_CONFIGS.with(|c| c.borrow().last().copied()),
+    };
+    scoped.unwrap_or_else(|| match GLOBAL_CONFIG_SET.load(Ordering::Acquire) {
+        false => FlexConfig::DEFAULT,
+        true => *GLOBAL_CONFIG.read().unwrap_or_else(|err| err.into_inner()),
+    })
+}
+
+/// Returns [`FlexConfig::DEFAULT`], there is no global configuration without the `std` feature.
+#[cfg(not(feature = "std"))]
+pub fn config() -> FlexConfig {
+    FlexConfig::DEFAULT
+}
+
+/// Runs `f` with `config` in place of the global configuration on this thread.
+/// Calls can be nested, the innermost one applies. Other threads, including threads spawned by `f`,
+/// are not affected.
+#[cfg(feature = "std")]
+pub fn with_config<R>(config: FlexConfig, f: impl FnOnce() -> R) -> R {
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            SCOPED_CONFIGS.with(|c| c.borrow_mut().pop());
+            SCOPED_CONFIG_COUNT.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    SCOPED_CONFIG_COUNT.fetch_add(1, Ordering::Relaxed);
+    SCOPED_CONFIGS.with(|c| c.borrow_mut().push(config));
+    let _guard = Guard;
+    f()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round() {
+        let round = |rounding, v| FlexConfig::new().rounding(rounding).round(v);
+        assert_eq!(round(Rounding::Nearest, 2.5), Some(3.0));
+        assert_eq!(round(Rounding::Nearest, -2.5), Some(-3.0));
+        assert_eq!(round(Rounding::Floor, -2.5), Some(-3.0));
+        assert_eq!(round(Rounding::Ceil, 2.1), Some(3.0));
+        assert_eq!(round(Rounding::Trunc, -2.9), Some(-2.0));
+        assert_eq!(round(Rounding::Reject, 2.0), Some(2.0));
+        assert_eq!(round(Rounding::Reject, 2.5), None);
+    }
+
+    #[test]
+    fn test_bool_words() {
+        let cfg = FlexConfig::new();
+        assert_eq!(cfg.bool_word("YES"), Some(true));
+        assert_eq!(cfg.bool_word("Off"), Some(false));
+        assert_eq!(cfg.bool_word("si"), None);
+        assert!(cfg.has_default_bool_words());
+
+        let cfg = cfg.bool_words(&["si", "ja"], &["no", "nein"]);
+        assert_eq!(cfg.bool_word("JA"), Some(true));
+        assert_eq!(cfg.bool_word("yes"), None);
+        assert!(!cfg.has_default_bool_words());
+    }
+
+    #[test]
+    fn test_with_config() {
+        let strict = FlexConfig::new().strict(true);
+        let trim = FlexConfig::new().trim(true);
+        assert_eq!(config(), FlexConfig::DEFAULT);
+
+        with_config(strict, || {
+            assert_eq!(config(), strict);
+            with_config(trim, || assert_eq!(config(), trim));
+            assert_eq!(config(), strict);
+            assert_eq!(std::thread::spawn(config).join().unwrap(), FlexConfig::DEFAULT);
+        });
+        assert_eq!(config(), FlexConfig::DEFAULT);
+
+        let result = std::panic::catch_unwind(|| with_config(strict, || panic!("boom")));
+        assert!(result.is_err());
+        assert_eq!(config(), FlexConfig::DEFAULT);
+    }
+
+    #[test]
+    fn test_flags() {
+        let cfg = FlexConfig::new().strict(true);
+        assert!(!cfg.floats() && !cfg.strings());
+        assert_eq!(FlexConfig::new().trim(true).trimmed(" 1 "), "1");
+        assert_eq!(FlexConfig::new().trimmed(" 1 "), " 1 ");
+        assert_eq!(FlexConfig::new().null_sentinels(&["-"]).sentinels(), ["-"]);
+        assert!(!FlexConfig::new().hinted());
+        assert!(FlexConfig::new().type_hints(TypeHints::Always).hinted());
+    }
+}
@@ -1,19 +1,40 @@
 pub(super) mod as_bool;
 pub(super) mod as_bool_opt;
 
+pub(super) mod as_char;
+pub(super) mod as_char_opt;
+
+#[cfg(feature = "alloc")]
+pub(super) mod as_cow_str;
+#[cfg(feature = "alloc")]
+pub(super) mod as_cow_str_opt;
+
 pub(super) mod as_f64;
 pub(super) mod as_f64_opt;
 
 pub(super) mod as_i64;
 pub(super) mod as_i64_opt;
 
+pub(super) mod as_num;
+
+#[cfg(feature = "alloc")]
 pub(super) mod as_string;
+#[cfg(feature = "alloc")]
 pub(super) mod as_string_opt;
+#[cfg(feature = "alloc")]
+pub(super) mod as_string_normalized;
 
 pub(super) mod as_u64;
 pub(super) mod as_u64_opt;
 
-mod unexpected;
+pub(super) mod as_flexible;
+pub(super) mod as_lenient;
+pub(super) mod as_or_default;
+pub(super) mod as_patch;
+
+mod classified;
+mod hinted;
+mod lexer;
 mod wrappers;
 
 
@@ -1,54 +1,90 @@
+use crate::config::{Config, FlexConfig, GlobalConfig};
+use crate::deserialize::hinted::{self, TypeHint};
+use crate::error::null_not_allowed;
 use serde::de::{Deserializer, Error, Expected, Unexpected, Visitor};
-use std::fmt;
+use core::fmt;
 
-const EXPECTED: &str = "an integer (0 or 1) or a case insensitive string (true/false, yes/no, on/off, y/n, t/f, 1/0, ok)";
+pub(super) const EXPECTED: &str = "an integer (0 or 1) or a case insensitive string (true/false, yes/no, on/off, y/n, t/f, 1/0, ok)";
+const STRICT_EXPECTED: &str = "a bool";
 
 pub fn as_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
-    deserializer.deserialize_any(AsBool)
+    as_bool_config::<GlobalConfig, D>(deserializer)
 }
 
-struct AsBool;
+/// Like `as_bool`, but with the coercion rules from `C` instead of the global configuration.
+pub fn as_bool_config<'de, C: Config, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    let cfg = C::config();
+    hinted::deserialize(deserializer, &cfg, TypeHint::Bool, AsBool(cfg))
+}
+
+struct AsBool(FlexConfig);
 
 impl<'de> Visitor<'de> for AsBool {
     type Value = bool;
 
-    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result { formatter.write_str(EXPECTED) }
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result { write_expected(formatter, &self.0, EXPECTED) }
     fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> { Ok(v) }
-    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { parse_i64(v, &EXPECTED) }
-    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { parse_u64(v, &EXPECTED) }
-    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { parse_str(v, &EXPECTED) }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { parse_i64(v, &self.0, &self) }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { parse_u64(v, &self.0, &self) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { parse_str(v, &self.0, &self) }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { Err(null_not_allowed(&self)) }
+}
+
+/// Writes `exp`, or a description of `cfg` if it is strict or has other words than the default ones.
+pub(super) fn write_expected(formatter: &mut fmt::Formatter<'_>, cfg: &FlexConfig, exp: &str) -> fmt::Result {
+    if !cfg.is_strict() && cfg.has_default_bool_words() {
+        return formatter.write_str(exp);
+    }
+    if exp.starts_with("null") {
+        formatter.write_str("null or ")?;
+    }
+    if cfg.is_strict() {
+        return formatter.write_str(STRICT_EXPECTED);
+    }
+    write!(formatter, "an integer (0 or 1) or a case insensitive string ({} or {})", Words(cfg.true_words()), Words(cfg.false_words()))
 }
 
-pub(super) fn parse_i64<E: Error>(v: i64, exp: &dyn Expected) -> Result<bool, E> {
+/// Writes words separated by `/`.
+struct Words(&'static [&'static str]);
+
+impl fmt::Display for Words {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, word) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+            f.write_str(word)?;
+        }
+        Ok(())
+    }
+}
+
+pub(super) fn parse_i64<E: Error>(v: i64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<bool, E> {
     match v {
+        _ if cfg.is_strict() => Err(Error::invalid_type(Unexpected::Signed(v), exp)),
         0 => Ok(false),
         1 => Ok(true),
         _ => Err(Error::invalid_value(Unexpected::Signed(v), exp)),
     }
 }
 
-pub(super) fn parse_u64<E: Error>(v: u64, exp: &dyn Expected) -> Result<bool, E> {
+pub(super) fn parse_u64<E: Error>(v: u64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<bool, E> {
     match v {
+        _ if cfg.is_strict() => Err(Error::invalid_type(Unexpected::Unsigned(v), exp)),
         0 => Ok(false),
         1 => Ok(true),
         _ => Err(Error::invalid_value(Unexpected::Unsigned(v), exp)),
     }
 }
 
-pub(super) fn parse_str<E: Error>(v: &str, exp: &dyn Expected) -> Result<bool, E> {
-    match v {
-        "1" | "true" | "True" => Ok(true),
-        "0" | "false" | "False" => Ok(false),
-        _ => {
-            match v.to_lowercase().as_str() {
-                "true" | "yes" | "on" | "y" | "t" | "ok" => Ok(true),
-                "false" | "no" | "off" | "n" | "f" => Ok(false),
-                _ => Err(Error::invalid_value(Unexpected::Str(v), exp)),
-            }
-        }
+pub(super) fn parse_str<E: Error>(v: &str, cfg: &FlexConfig, exp: &dyn Expected) -> Result<bool, E> {
+    if !cfg.strings() {
+        return Err(Error::invalid_type(Unexpected::Str(v), exp));
     }
+    cfg.bool_word(cfg.trimmed(v)).ok_or_else(|| Error::invalid_value(Unexpected::Str(v), exp))
 }
 
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,4 +159,33 @@ mod tests {
         assert!(serde_json::from_str::<Test>(r#"{"bool": -100}"#).unwrap_err().to_string().contains(EXPECTED));
         assert!(serde_json::from_str::<Test>(r#"{"bool": "unknown"}"#).unwrap_err().to_string().contains(EXPECTED));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_config() {
+        use crate::config::{Config, FlexConfig};
+
+        struct German;
+        impl Config for German {
+            fn config() -> FlexConfig { FlexConfig::new().bool_words(&["ja", "wahr"], &["nein", "falsch"]).trim(true) }
+        }
+
+        struct Strict;
+        impl Config for Strict {
+            fn config() -> FlexConfig { FlexConfig::new().strict(true) }
+        }
+
+        fn parse<C: Config>(json: &str) -> Result<bool, serde_json::Error> {
+            as_bool_config::<C, _>(&mut serde_json::Deserializer::from_str(json))
+        }
+
+        assert!(parse::<German>(r#"" JA ""#).unwrap());
+        assert!(!parse::<German>(r#""Falsch""#).unwrap());
+        assert!(parse::<German>("1").unwrap());
+        let err = parse::<German>(r#""yes""#).unwrap_err().to_string();
+        assert!(err.contains("a case insensitive string (ja/wahr or nein/falsch)"), "{err}");
+
+        assert!(parse::<Strict>("true").unwrap());
+        assert!(parse::<Strict>("1").unwrap_err().to_string().contains("expected a bool"));
+        assert!(parse::<Strict>(r#""true""#).is_err());
+    }
+}
@@ -1,27 +1,62 @@
 use super::as_bool;
+use crate::config::{config, Config, FlexConfig, GlobalConfig};
+use crate::deserialize::hinted::{self, TypeHint};
 use crate::deserialize::wrappers::str_wrap_as_opt;
+use crate::sentinels::{write_expecting, NullSentinels};
 use serde::de::{Deserializer, Error, Visitor};
-use std::fmt;
+use core::fmt;
 
-const EXPECTED: &str = "null or an integer (0 or 1) or a case insensitive string (true/false, yes/no, y/n, t/f, 1/0, on/off, ok, null, none, unknown)";
+pub(super) const EXPECTED: &str = "null or an integer (0 or 1) or a case insensitive string (true/false, yes/no, y/n, t/f, 1/0, on/off, ok)";
 
 pub fn as_bool_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
-    deserializer.deserialize_any(AsOptBool)
+    as_bool_opt_config::<GlobalConfig, D>(deserializer)
 }
 
-struct AsOptBool;
+/// Like `as_bool_opt`, but with the null sentinels from `S` instead of the global set.
+pub fn as_bool_opt_with<'de, S: NullSentinels, D: Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
+    let cfg = config();
+    hinted::deserialize_opt(deserializer, &cfg, TypeHint::Bool, AsOptBool::new(cfg, S::sentinels()))
+}
+
+/// Like `as_bool_opt`, but with the coercion rules and null sentinels from `C` instead of the global configuration.
+pub fn as_bool_opt_config<'de, C: Config, D: Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
+    let cfg = C::config();
+    hinted::deserialize_opt(deserializer, &cfg, TypeHint::Bool, AsOptBool::from_config(cfg))
+}
+
+pub(super) struct AsOptBool {
+    cfg: FlexConfig,
+    sentinels: &'static [&'static str],
+}
+
+impl AsOptBool {
+    pub(super) fn new(cfg: FlexConfig, sentinels: &'static [&'static str]) -> Self {
+        AsOptBool { cfg, sentinels }
+    }
+
+    pub(super) fn from_config(cfg: FlexConfig) -> Self {
+        AsOptBool::new(cfg, cfg.sentinels())
+    }
+}
 
 impl<'de> Visitor<'de> for AsOptBool {
     type Value = Option<bool>;
 
-    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result { formatter.write_str(EXPECTED) }
-    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> { Ok(v).map(Some) }
-    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { as_bool::parse_i64(v, &EXPECTED).map(Some) }
-    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { as_bool::parse_u64(v, &EXPECTED).map(Some) }
-    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { str_wrap_as_opt(v, &EXPECTED, as_bool::parse_str) }
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        as_bool::write_expected(formatter, &self.cfg, EXPECTED)?;
+        write_expecting(formatter, "", self.sentinels)
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> { Ok(Some(v)) }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { as_bool::parse_i64(v, &self.cfg, &self).map(Some) }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { as_bool::parse_u64(v, &self.cfg, &self).map(Some) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        str_wrap_as_opt(self.cfg.trimmed(v), &self, self.sentinels, |v, exp| as_bool::parse_str(v, &self.cfg, exp))
+    }
     fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { Ok(None) }
 }
 
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,125 @@
+use crate::config::{Config, FlexConfig, GlobalConfig};
+use crate::deserialize::hinted::{self, TypeHint};
+use crate::deserialize::wrappers::bytes_as_str;
+use crate::error::{null_not_allowed, out_of_range};
+use serde::de::{Deserializer, Error, Expected, Unexpected, Visitor};
+use core::fmt;
+
+const EXPECTED: &str = "a single character, a Unicode code point, or a one-character string";
+const STRICT_EXPECTED: &str = "a single character or a one-character string";
+
+pub fn as_char<'de, D: Deserializer<'de>>(deserializer: D) -> Result<char, D::Error> {
+    as_char_config::<GlobalConfig, D>(deserializer)
+}
+
+/// Like `as_char`, but with the coercion rules from `C` instead of the global configuration.
+pub fn as_char_config<'de, C: Config, D: Deserializer<'de>>(deserializer: D) -> Result<char, D::Error> {
+    let cfg = C::config();
+    hinted::deserialize(deserializer, &cfg, TypeHint::Char, AsChar(cfg))
+}
+
+pub(super) fn parse_u64<E: Error>(v: u64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<char, E> {
+    if cfg.is_strict() {
+        return Err(Error::invalid_type(Unexpected::Unsigned(v), exp));
+    }
+    match u32::try_from(v) {
+        // surrogates are in range, but not chars
+        Ok(u32) if u32 <= char::MAX as u32 => {
+            char::from_u32(u32).ok_or_else(|| Error::invalid_value(Unexpected::Unsigned(v), exp))
+        }
+        _ => Err(out_of_range(Unexpected::Unsigned(v), exp)),
+    }
+}
+
+pub(super) fn parse_i64<E: Error>(v: i64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<char, E> {
+    if cfg.is_strict() {
+        return Err(Error::invalid_type(Unexpected::Signed(v), exp));
+    }
+    u64::try_from(v)
+        .map_err(|_| out_of_range(Unexpected::Signed(v), exp))
+        .and_then(|v| parse_u64(v, cfg, exp))
+}
+
+pub(super) fn parse_str<E: Error>(v: &str, cfg: &FlexConfig, exp: &dyn Expected) -> Result<char, E> {
+    // a single whitespace character is a valid value, padding is only trimmed around other characters
+    let mut chars = v.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Ok(c);
+    }
+
+    let trimmed = if cfg.is_strict() { v } else { v.trim() };
+    let mut chars = trimmed.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => Err(Error::invalid_value(Unexpected::Str(v), exp)),
+    }
+}
+
+pub(super) fn expected(cfg: &FlexConfig) -> &'static str {
+    if cfg.is_strict() { STRICT_EXPECTED } else { EXPECTED }
+}
+
+struct AsChar(FlexConfig);
+
+impl<'de> Visitor<'de> for AsChar {
+    type Value = char;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result { formatter.write_str(expected(&self.0)) }
+    fn visit_char<E: Error>(self, v: char) -> Result<Self::Value, E> { Ok(v) }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { parse_i64(v, &self.0, &self) }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { parse_u64(v, &self.0, &self) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { parse_str(v, &self.0, &self) }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> { parse_str(bytes_as_str(v, &self)?, &self.0, &self) }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { Err(null_not_allowed(&self)) }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+        #[serde(deserialize_with = "as_char")]
+        char: char,
+    }
+
+    #[test]
+    fn test_base_good_parse() {
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": "A"      }"#).unwrap().char, 'A');
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": " A "    }"#).unwrap().char, 'A');
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": "\tb\n"  }"#).unwrap().char, 'b');
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": "é"      }"#).unwrap().char, 'é');
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": " "      }"#).unwrap().char, ' ');
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": ","      }"#).unwrap().char, ',');
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": "7"      }"#).unwrap().char, '7');
+
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": 65       }"#).unwrap().char, 'A');
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": 233      }"#).unwrap().char, 'é');
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": 0        }"#).unwrap().char, '\0');
+    }
+
+    #[test]
+    fn test_base_error() {
+        assert!(serde_json::from_str::<Test>(r#"{"char": ""         }"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"char": "  "       }"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"char": "AB"       }"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"char": " A B "    }"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"char": null       }"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"char": true       }"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"char": 65.0       }"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"char": -1         }"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"char": 55296      }"#).is_err()); // surrogate
+        assert!(serde_json::from_str::<Test>(r#"{"char": 4294967296 }"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_error_message() {
+        assert!(serde_json::from_str::<Test>(r#"{"char": null}"#).unwrap_err().to_string().contains(EXPECTED));
+        assert!(serde_json::from_str::<Test>(r#"{"char": -1}"#).unwrap_err().to_string().contains(EXPECTED));
+
+        let err = serde_json::from_str::<Test>(r#"{"char": "ABC"}"#).unwrap_err().to_string();
+        assert!(err.contains(r#"invalid value: string "ABC""#) && err.contains(EXPECTED));
+    }
+}
@@ -0,0 +1,101 @@
+use super::as_char;
+use crate::config::{config, Config, FlexConfig, GlobalConfig};
+use crate::deserialize::hinted::{self, TypeHint};
+use crate::deserialize::wrappers::{bytes_as_str, str_wrap_as_opt};
+use crate::sentinels::{write_expecting, NullSentinels};
+use serde::de::{Deserializer, Error, Visitor};
+use core::fmt;
+
+pub(super) const EXPECTED: &str = "null, a single character, a Unicode code point, or a one-character string";
+const STRICT_EXPECTED: &str = "null, a single character, or a one-character string";
+
+pub fn as_char_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<char>, D::Error> {
+    as_char_opt_config::<GlobalConfig, D>(deserializer)
+}
+
+/// Like `as_char_opt`, but with the null sentinels from `S` instead of the global set.
+pub fn as_char_opt_with<'de, S: NullSentinels, D: Deserializer<'de>>(deserializer: D) -> Result<Option<char>, D::Error> {
+    let cfg = config();
+    hinted::deserialize_opt(deserializer, &cfg, TypeHint::Char, AsOptChar::new(cfg, S::sentinels()))
+}
+
+/// Like `as_char_opt`, but with the coercion rules and null sentinels from `C` instead of the global configuration.
+pub fn as_char_opt_config<'de, C: Config, D: Deserializer<'de>>(deserializer: D) -> Result<Option<char>, D::Error> {
+    let cfg = C::config();
+    hinted::deserialize_opt(deserializer, &cfg, TypeHint::Char, AsOptChar::from_config(cfg))
+}
+
+pub(super) struct AsOptChar {
+    cfg: FlexConfig,
+    sentinels: &'static [&'static str],
+}
+
+impl AsOptChar {
+    pub(super) fn new(cfg: FlexConfig, sentinels: &'static [&'static str]) -> Self {
+        AsOptChar { cfg, sentinels }
+    }
+
+    pub(super) fn from_config(cfg: FlexConfig) -> Self {
+        AsOptChar::new(cfg, cfg.sentinels())
+    }
+}
+
+impl<'de> Visitor<'de> for AsOptChar {
+    type Value = Option<char>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_expecting(formatter, if self.cfg.is_strict() { STRICT_EXPECTED } else { EXPECTED }, self.sentinels)
+    }
+
+    fn visit_char<E: Error>(self, v: char) -> Result<Self::Value, E> { Ok(Some(v)) }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { as_char::parse_i64(v, &self.cfg, &self).map(Some) }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { as_char::parse_u64(v, &self.cfg, &self).map(Some) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        str_wrap_as_opt(v, &self, self.sentinels, |v, exp| as_char::parse_str(v, &self.cfg, exp))
+    }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        let v = bytes_as_str(v, &self)?;
+        self.visit_str(v)
+    }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { Ok(None) }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+        #[serde(deserialize_with = "as_char_opt")]
+        char: Option<char>,
+    }
+
+    #[test]
+    fn test_base_good_parse() {
+        assert!(serde_json::from_str::<Test>(r#"{"char": null       }"#).unwrap().char.is_none());
+        assert!(serde_json::from_str::<Test>(r#"{"char": "null"     }"#).unwrap().char.is_none());
+        assert!(serde_json::from_str::<Test>(r#"{"char": "None"     }"#).unwrap().char.is_none());
+        assert!(serde_json::from_str::<Test>(r#"{"char": "unknown"  }"#).unwrap().char.is_none());
+
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": "A"      }"#).unwrap().char.unwrap(), 'A');
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": " A "    }"#).unwrap().char.unwrap(), 'A');
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": "n"      }"#).unwrap().char.unwrap(), 'n');
+        assert_eq!(serde_json::from_str::<Test>(r#"{"char": 65       }"#).unwrap().char.unwrap(), 'A');
+    }
+
+    #[test]
+    fn test_base_error() {
+        assert!(serde_json::from_str::<Test>(r#"{"char": ""         }"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"char": "AB"       }"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"char": -1         }"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"char": false      }"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_error_message() {
+        assert!(serde_json::from_str::<Test>(r#"{"char": "AB"}"#).unwrap_err().to_string().contains(EXPECTED));
+        assert!(serde_json::from_str::<Test>(r#"{"char": [65]}"#).unwrap_err().to_string().contains(EXPECTED));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::config::config;
+use crate::deserialize::hinted::{self, TypeHint};
+use crate::deserialize::wrappers::bytes_as_str;
+use crate::error::null_not_allowed;
+use serde::de::{Deserializer, Error, Unexpected, Visitor};
+use alloc::borrow::Cow;
+use core::fmt;
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+const EXPECTED: &str = "a string, bool, a number, or UTF-8 bytes";
+
+pub fn as_cow_str<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Cow<'de, str>, D::Error> {
+    hinted::deserialize(deserializer, &config(), TypeHint::Str, AsCowStr)
+}
+
+struct AsCowStr;
+
+impl<'de> Visitor<'de> for AsCowStr {
+    type Value = Cow<'de, str>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(EXPECTED)
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.to_string()))
+    }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.to_string()))
+    }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.to_string()))
+    }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.to_string()))
+    }
+    fn visit_borrowed_str<E: Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(Cow::Borrowed(v))
+    }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v.to_owned()))
+    }
+    fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(v))
+    }
+    fn visit_borrowed_bytes<E: Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        bytes_as_str(v, &EXPECTED).map(Cow::Borrowed)
+    }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        bytes_as_str(v, &EXPECTED).map(|v| Cow::Owned(v.to_owned()))
+    }
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        match String::from_utf8(v) {
+            Ok(v) => Ok(Cow::Owned(v)),
+            Err(err) => Err(Error::invalid_value(Unexpected::Bytes(err.as_bytes()), &EXPECTED)),
+        }
+    }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        Err(null_not_allowed(&self))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test<'a> {
+        #[serde(borrow, deserialize_with = "as_cow_str")]
+        str: Cow<'a, str>,
+    }
+
+    #[test]
+    fn test_base_good_parse() {
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": "Foo Boo" }"#).unwrap().str, "Foo Boo");
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": "hello"   }"#).unwrap().str, "hello");
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": 100       }"#).unwrap().str, "100");
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": true      }"#).unwrap().str, "true");
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": false     }"#).unwrap().str, "false");
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": -12345    }"#).unwrap().str, "-12345");
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": 3.14      }"#).unwrap().str, "3.14");
+    }
+
+    #[test]
+    fn test_borrowing() {
+        assert!(matches!(serde_json::from_str::<Test>(r#"{"str": "hello"     }"#).unwrap().str, Cow::Borrowed("hello")));
+        assert!(matches!(serde_json::from_str::<Test>(r#"{"str": 100         }"#).unwrap().str, Cow::Owned(_)));
+        // escaped strings can't be borrowed from the input
+        assert!(matches!(serde_json::from_str::<Test>(r#"{"str": "hel\"lo"   }"#).unwrap().str, Cow::Owned(_)));
+    }
+
+    #[test]
+    fn test_bytes() {
+        use serde::de::value::{BorrowedBytesDeserializer, BytesDeserializer, Error};
+
+        assert!(matches!(as_cow_str(BorrowedBytesDeserializer::<Error>::new(b"hello")).unwrap(), Cow::Borrowed("hello")));
+        assert!(matches!(as_cow_str(BytesDeserializer::<Error>::new(b"hello")).unwrap(), Cow::Owned(_)));
+        assert!(as_cow_str(BorrowedBytesDeserializer::<Error>::new(b"\xff")).is_err());
+    }
+
+    #[test]
+    fn test_base_error() {
+        assert!(serde_json::from_str::<Test>(r#"{"str": null         }"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"str": ["hello"]]   }"#).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+use crate::config::config;
+use crate::deserialize::hinted::{self, TypeHint};
+use crate::deserialize::wrappers::bytes_as_str;
+use crate::sentinels::{is_sentinel, write_expecting, NullSentinels};
+use serde::de::{Deserializer, Error, Unexpected, Visitor};
+use alloc::borrow::Cow;
+use core::fmt;
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+const EXPECTED: &str = "null, a string, bool, a number, or UTF-8 bytes";
+
+pub fn as_cow_str_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Cow<'de, str>>, D::Error> {
+    hinted::deserialize_opt(deserializer, &config(), TypeHint::Str, AsOptCowStr { sentinels: None })
+}
+
+/// Like `as_cow_str_opt`, but blank strings and the null sentinels from `S` are `None`, like `as_string_opt_with`.
+pub fn as_cow_str_opt_with<'de, S: NullSentinels, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Cow<'de, str>>, D::Error> {
+    hinted::deserialize_opt(deserializer, &config(), TypeHint::Str, AsOptCowStr { sentinels: Some(S::sentinels()) })
+}
+
+struct AsOptCowStr {
+    /// The null sentinels, `None` to keep all strings.
+    sentinels: Option<&'static [&'static str]>,
+}
+
+impl AsOptCowStr {
+    fn some<'de>(&self, v: Cow<'de, str>) -> Option<Cow<'de, str>> {
+        match self.sentinels {
+            Some(sentinels) if v.trim().is_empty() || is_sentinel(sentinels, &v) => None,
+            _ => Some(v),
+        }
+    }
+}
+
+impl<'de> Visitor<'de> for AsOptCowStr {
+    type Value = Option<Cow<'de, str>>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.sentinels {
+            Some(sentinels) => write_expecting(formatter, EXPECTED, sentinels),
+            None => formatter.write_str(EXPECTED),
+        }
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Some(Cow::Owned(v.to_string())))
+    }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Some(Cow::Owned(v.to_string())))
+    }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Some(Cow::Owned(v.to_string())))
+    }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Some(Cow::Owned(v.to_string())))
+    }
+    fn visit_borrowed_str<E: Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(self.some(Cow::Borrowed(v)))
+    }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(self.some(Cow::Owned(v.to_owned())))
+    }
+    fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(self.some(Cow::Owned(v)))
+    }
+    fn visit_borrowed_bytes<E: Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        bytes_as_str(v, &EXPECTED).map(|v| self.some(Cow::Borrowed(v)))
+    }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        bytes_as_str(v, &EXPECTED).map(|v| self.some(Cow::Owned(v.to_owned())))
+    }
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        match String::from_utf8(v) {
+            Ok(v) => Ok(self.some(Cow::Owned(v))),
+            Err(err) => Err(Error::invalid_value(Unexpected::Bytes(err.as_bytes()), &EXPECTED)),
+        }
+    }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test<'a> {
+        #[serde(borrow, deserialize_with = "as_cow_str_opt")]
+        str: Option<Cow<'a, str>>,
+    }
+
+    #[test]
+    fn test_base_good_parse() {
+        // like as_string_opt, strings like "null" or "none" are kept as is
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": "null"    }"#).unwrap().str.unwrap(), "null");
+        assert!(serde_json::from_str::<Test>(r#"{"str": null         }"#).unwrap().str.is_none());
+
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": "Foo Boo" }"#).unwrap().str.unwrap(), "Foo Boo");
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": 100       }"#).unwrap().str.unwrap(), "100");
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": true      }"#).unwrap().str.unwrap(), "true");
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": 3.14      }"#).unwrap().str.unwrap(), "3.14");
+    }
+
+    #[test]
+    fn test_borrowing() {
+        assert!(matches!(serde_json::from_str::<Test>(r#"{"str": "hello"   }"#).unwrap().str, Some(Cow::Borrowed("hello"))));
+        assert!(matches!(serde_json::from_str::<Test>(r#"{"str": -12345    }"#).unwrap().str, Some(Cow::Owned(_))));
+    }
+
+    #[test]
+    fn test_bytes() {
+        use serde::de::value::{BorrowedBytesDeserializer, BytesDeserializer, Error};
+
+        assert!(matches!(as_cow_str_opt(BorrowedBytesDeserializer::<Error>::new(b"hello")).unwrap(), Some(Cow::Borrowed("hello"))));
+        assert!(matches!(as_cow_str_opt(BytesDeserializer::<Error>::new(b"hello")).unwrap(), Some(Cow::Owned(_))));
+        assert!(as_cow_str_opt(BorrowedBytesDeserializer::<Error>::new(b"\xff")).is_err());
+    }
+
+    #[test]
+    fn test_sentinels() {
+        struct Dash;
+
+        impl NullSentinels for Dash {
+            fn sentinels() -> &'static [&'static str] { &["-", "N/A"] }
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct With<'a> {
+            #[serde(borrow, deserialize_with = "as_cow_str_opt_with::<Dash, _>")]
+            str: Option<Cow<'a, str>>,
+        }
+
+        assert!(serde_json::from_str::<With>(r#"{"str": "-"}"#).unwrap().str.is_none());
+        assert!(serde_json::from_str::<With>(r#"{"str": "n/a"}"#).unwrap().str.is_none());
+        assert!(serde_json::from_str::<With>(r#"{"str": " "}"#).unwrap().str.is_none());
+        assert!(matches!(serde_json::from_str::<With>(r#"{"str": "none"}"#).unwrap().str, Some(Cow::Borrowed("none"))));
+        let err = serde_json::from_str::<With>(r#"{"str": []}"#).unwrap_err().to_string();
+        assert!(err.contains(r#"(null sentinels: "-", "N/A")"#), "{err}");
+    }
+
+    #[test]
+    fn test_base_error() {
+        assert!(serde_json::from_str::<Test>(r#"{"str": ["hello"]]   }"#).is_err());
+    }
+}
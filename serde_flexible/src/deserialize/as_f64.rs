@@ -1,22 +1,39 @@
+use crate::config::{Config, FlexConfig, GlobalConfig};
+use crate::deserialize::as_num::as_num;
+use crate::deserialize::lexer::{self, lex, Token};
+use crate::deserialize::wrappers::bytes_as_str;
+use crate::error::null_not_allowed;
 use serde::de::{Deserializer, Error, Expected, Unexpected, Visitor};
-use std::fmt;
+use core::fmt;
 
 const EXPECTED: &str = "a float, an integer, or a string";
+const STRICT_EXPECTED: &str = "a float or an integer";
 
 pub fn as_f64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
-    deserializer.deserialize_any(AsFloat)
+    as_num::<f64, GlobalConfig, D>(deserializer)
 }
 
-struct AsFloat;
+/// Like `as_f64`, but with the coercion rules from `C` instead of the global configuration.
+pub fn as_f64_config<'de, C: Config, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    as_num::<f64, C, D>(deserializer)
+}
+
+pub(super) fn expected(cfg: &FlexConfig) -> &'static str {
+    if cfg.is_strict() { STRICT_EXPECTED } else { EXPECTED }
+}
+
+pub(super) struct AsFloat(pub(super) FlexConfig);
 
 impl<'de> Visitor<'de> for AsFloat {
     type Value = f64;
 
-    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result { formatter.write_str(EXPECTED) }
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result { formatter.write_str(expected(&self.0)) }
     fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { parse_i64(v) }
     fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { parse_u64(v) }
     fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { Ok(v) }
-    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { parse_str(v, &EXPECTED) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { parse_str(v, &self.0, &self) }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> { parse_str(bytes_as_str(v, &self)?, &self.0, &self) }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { Err(null_not_allowed(&self)) }
 }
 
 pub(super) fn parse_i64<E: Error>(v: i64) -> Result<f64, E> {
@@ -27,16 +44,27 @@ pub(super) fn parse_u64<E: Error>(v: u64) -> Result<f64, E> {
     Ok(v as f64)
 }
 
-pub(super) fn parse_str<E: Error>(v: &str, exp: &dyn Expected) -> Result<f64, E> {
-    match v.parse::<f64>() {
-        Ok(f) => Ok(f),
-        Err(_) => Err(E::invalid_value(Unexpected::Str(v), exp)),
-    }
+pub(super) fn parse_str<E: Error>(v: &str, cfg: &FlexConfig, exp: &dyn Expected) -> Result<f64, E> {
+    let v = cfg.trimmed(v);
+    parse_token(v, lex(v, &[]), cfg, exp)
 }
 
+/// Parses `v`, a string already trimmed and classified as `token`.
+pub(super) fn parse_token<E: Error>(v: &str, token: Token, cfg: &FlexConfig, exp: &dyn Expected) -> Result<f64, E> {
+    if !cfg.strings() {
+        return Err(Error::invalid_type(Unexpected::Str(v), exp));
+    }
+    match token {
+        Token::Integer { negative, magnitude: Some(u64) } => Ok(if negative { -(u64 as f64) } else { u64 as f64 }),
+        Token::Integer { .. } | Token::Decimal | Token::Exponent => lexer::float(v, exp),
+        // `inf`, `infinity` and `NaN`
+        Token::Word | Token::Sentinel => v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), exp)),
+    }
+}
 
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
     use serde::Deserialize;
@@ -76,4 +104,14 @@ mod tests {
     fn test_parse_error_message() {
         assert!(serde_json::from_str::<Test>(r#"{"float": null}"#).unwrap_err().to_string().contains(EXPECTED));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bytes() {
+        use serde::de::value::{BytesDeserializer, Error};
+
+        assert_eq!(as_f64(BytesDeserializer::<Error>::new(b"-3.5")).unwrap(), -3.5);
+        assert_eq!(as_f64(BytesDeserializer::<Error>::new(b"3")).unwrap(), 3.0);
+        assert!(as_f64(BytesDeserializer::<Error>::new(b"abc")).is_err());
+        assert!(as_f64(BytesDeserializer::<Error>::new(b"\xff1")).unwrap_err().to_string().contains(EXPECTED));
+    }
+}
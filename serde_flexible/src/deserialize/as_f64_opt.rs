@@ -1,29 +1,68 @@
-use crate::deserialize::as_f64;
-use crate::deserialize::wrappers::str_wrap_as_opt;
+use super::as_f64;
+use crate::config::{config, Config, FlexConfig, GlobalConfig};
+use crate::deserialize::as_num::as_num_opt;
+use crate::deserialize::hinted::{self, TypeHint};
+use crate::deserialize::wrappers::{bytes_as_str, lex_as_opt};
+use crate::sentinels::{write_expecting, NullSentinels};
 use serde::de::{Deserializer, Error, Visitor};
-use std::fmt;
+use core::fmt;
 
-const EXPECTED: &str = "null, a float, an integer, or a string";
+pub(super) const EXPECTED: &str = "null, a float, an integer, or a string";
+const STRICT_EXPECTED: &str = "null, a float, or an integer";
 
 pub fn as_f64_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
-    deserializer.deserialize_any(AsOptFloat)
+    as_num_opt::<f64, GlobalConfig, D>(deserializer)
 }
 
-struct AsOptFloat;
+/// Like `as_f64_opt`, but with the null sentinels from `S` instead of the global set.
+pub fn as_f64_opt_with<'de, S: NullSentinels, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    let cfg = config();
+    hinted::deserialize_opt(deserializer, &cfg, TypeHint::F64, AsOptFloat::new(cfg, S::sentinels()))
+}
+
+/// Like `as_f64_opt`, but with the coercion rules and null sentinels from `C` instead of the global configuration.
+pub fn as_f64_opt_config<'de, C: Config, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    as_num_opt::<f64, C, D>(deserializer)
+}
+
+pub(super) struct AsOptFloat {
+    cfg: FlexConfig,
+    sentinels: &'static [&'static str],
+}
+
+impl AsOptFloat {
+    pub(super) fn new(cfg: FlexConfig, sentinels: &'static [&'static str]) -> Self {
+        AsOptFloat { cfg, sentinels }
+    }
+
+    pub(super) fn from_config(cfg: FlexConfig) -> Self {
+        AsOptFloat::new(cfg, cfg.sentinels())
+    }
+}
 
 impl<'de> Visitor<'de> for AsOptFloat {
     type Value = Option<f64>;
 
-    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result { formatter.write_str(EXPECTED) }
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_expecting(formatter, if self.cfg.is_strict() { STRICT_EXPECTED } else { EXPECTED }, self.sentinels)
+    }
+
     fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { as_f64::parse_i64(v).map(Some) }
     fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { as_f64::parse_u64(v).map(Some) }
-    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { Ok(v).map(Some) }
-    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { str_wrap_as_opt(v, &EXPECTED, as_f64::parse_str) }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { Ok(Some(v)) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        lex_as_opt(self.cfg.trimmed(v), &self, self.sentinels, |v, token, exp| as_f64::parse_token(v, token, &self.cfg, exp))
+    }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        let v = bytes_as_str(v, &self)?;
+        self.visit_str(v)
+    }
     fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { Ok(None) }
 }
 
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     use super::*;
     use serde::Deserialize;
@@ -70,4 +109,13 @@ mod tests {
     fn test_parse_error_message() {
         assert!(serde_json::from_str::<Test>(r#"{"float": ["hello"]}"#).unwrap_err().to_string().contains(EXPECTED));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bytes() {
+        use serde::de::value::{BytesDeserializer, Error};
+
+        assert_eq!(as_f64_opt(BytesDeserializer::<Error>::new(b"2.5")).unwrap(), Some(2.5));
+        assert_eq!(as_f64_opt(BytesDeserializer::<Error>::new(b"unknown")).unwrap(), None);
+        assert!(as_f64_opt(BytesDeserializer::<Error>::new(b"\xff1")).is_err());
+    }
+}
@@ -0,0 +1,353 @@
+use super::{as_bool, as_char, as_f64, as_i64, as_u64};
+use crate::config::{Config, FlexConfig, GlobalConfig};
+use crate::deserialize::hinted::{self, Hint};
+use crate::deserialize::lexer::{self, lex, Token};
+use crate::deserialize::wrappers::bytes_as_str;
+use crate::sentinels::{is_sentinel, write_sentinels};
+use crate::error::null_not_allowed;
+use serde::de::{Deserializer, Error, Expected, Unexpected, Visitor};
+use core::fmt;
+use core::marker::PhantomData;
+
+/// Coercions of a type deserialized with [`as_flexible`], one hook per kind of input.
+///
+/// Every hook rejects its input by default, except `from_str`, `from_bytes` and `from_char`.
+/// These parse the string as an unsigned integer, an integer or a float and pass it on to
+/// `from_u64`, `from_i64` or `from_f64`. The impls for `bool`, `char`, `f64`, `i64` and `u64`
+/// follow the matching `as_*` deserializer, so a type can delegate to them:
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde::de::{Error, Expected};
+/// use serde_flexible::config::FlexConfig;
+/// use serde_flexible::{as_flexible, FlexibleParse};
+/// use std::fmt;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct AccountId(u64);
+///
+/// impl FlexibleParse for AccountId {
+///     fn expecting(formatter: &mut fmt::Formatter<'_>, _: &FlexConfig) -> fmt::Result {
+///         formatter.write_str("an account id")
+///     }
+///
+///     fn from_u64<E: Error>(v: u64, _: &FlexConfig, _: &dyn Expected) -> Result<Self, E> {
+///         Ok(AccountId(v))
+///     }
+///
+///     fn from_f64<E: Error>(v: f64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> {
+///         u64::from_f64(v, cfg, exp).map(AccountId)
+///     }
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Row {
+///     #[serde(deserialize_with = "as_flexible")]
+///     account: AccountId,
+/// }
+///
+/// assert_eq!(serde_json::from_str::<Row>(r#"{"account": "7"}"#).unwrap().account, AccountId(7));
+/// assert_eq!(serde_json::from_str::<Row>(r#"{"account": 7.0}"#).unwrap().account, AccountId(7));
+/// assert!(serde_json::from_str::<Row>(r#"{"account": -7}"#).is_err());
+/// ```
+pub trait FlexibleParse: Sized {
+    /// Writes what the deserializer expects, for error messages.
+    fn expecting(formatter: &mut fmt::Formatter<'_>, cfg: &FlexConfig) -> fmt::Result;
+
+    fn from_bool<E: Error>(v: bool, _cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> {
+        Err(Error::invalid_type(Unexpected::Bool(v), exp))
+    }
+
+    fn from_i64<E: Error>(v: i64, _cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> {
+        Err(Error::invalid_type(Unexpected::Signed(v), exp))
+    }
+
+    fn from_u64<E: Error>(v: u64, _cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> {
+        Err(Error::invalid_type(Unexpected::Unsigned(v), exp))
+    }
+
+    fn from_f64<E: Error>(v: f64, _cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> {
+        Err(Error::invalid_type(Unexpected::Float(v), exp))
+    }
+
+    fn from_str<E: Error>(v: &str, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> {
+        if !cfg.strings() {
+            return Err(Error::invalid_type(Unexpected::Str(v), exp));
+        }
+        let trimmed = cfg.trimmed(v);
+        match lex(trimmed, &[]) {
+            Token::Integer { negative: false, magnitude: Some(u64) } => Self::from_u64(u64, cfg, exp),
+            Token::Integer { negative: true, magnitude: Some(u64) } => match 0i64.checked_sub_unsigned(u64) {
+                Some(i64) => Self::from_i64(i64, cfg, exp),
+                None => Self::from_f64(lexer::float(trimmed, exp)?, cfg, exp),
+            },
+            Token::Integer { .. } | Token::Decimal | Token::Exponent => Self::from_f64(lexer::float(trimmed, exp)?, cfg, exp),
+            // `inf`, `infinity` and `NaN`
+            Token::Word | Token::Sentinel => match trimmed.parse::<f64>() {
+                Ok(f64) => Self::from_f64(f64, cfg, exp),
+                Err(_) => Err(Error::invalid_value(Unexpected::Str(v), exp)),
+            },
+        }
+    }
+
+    fn from_bytes<E: Error>(v: &[u8], cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> {
+        Self::from_str(bytes_as_str(v, exp)?, cfg, exp)
+    }
+
+    fn from_char<E: Error>(v: char, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> {
+        Self::from_str(v.encode_utf8(&mut [0; 4]), cfg, exp)
+    }
+
+    /// Calls the `deserialize_*` method for formats that are not self-describing, see
+    /// [`TypeHints`](crate::config::TypeHints). Override it with the method that matches how
+    /// the type is serialized, e.g. `deserializer.deserialize_u64(visitor)`. By default
+    /// `deserialize_any` is called, which these formats reject.
+    fn deserialize_hinted<'de, D: Deserializer<'de>, V: Visitor<'de>>(deserializer: D, visitor: V) -> Result<V::Value, D::Error> {
+        deserializer.deserialize_any(visitor)
+    }
+}
+
+/// Deserializes any [`FlexibleParse`] type with the global configuration.
+pub fn as_flexible<'de, T: FlexibleParse, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    as_flexible_config::<T, GlobalConfig, D>(deserializer)
+}
+
+/// Like `as_flexible`, but with the coercion rules from `C` instead of the global configuration.
+pub fn as_flexible_config<'de, T: FlexibleParse, C: Config, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    let cfg = C::config();
+    hinted::deserialize(deserializer, &cfg, ParseHint::<T>(PhantomData), AsFlexible(cfg, PhantomData))
+}
+
+/// Like `as_flexible`, but reads null and the null sentinels as `None`.
+pub fn as_flexible_opt<'de, T: FlexibleParse, D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error> {
+    as_flexible_opt_config::<T, GlobalConfig, D>(deserializer)
+}
+
+/// Like `as_flexible_opt`, but with the coercion rules and null sentinels from `C` instead of the global configuration.
+pub fn as_flexible_opt_config<'de, T: FlexibleParse, C: Config, D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error> {
+    let cfg = C::config();
+    hinted::deserialize_opt(deserializer, &cfg, ParseHint::<T>(PhantomData), AsFlexibleOpt(AsFlexible(cfg, PhantomData)))
+}
+
+struct ParseHint<T>(PhantomData<T>);
+
+impl<T> Clone for ParseHint<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ParseHint<T> {}
+
+impl<T: FlexibleParse> Hint for ParseHint<T> {
+    fn deserialize<'de, D: Deserializer<'de>, V: Visitor<'de>>(self, deserializer: D, visitor: V) -> Result<V::Value, D::Error> {
+        T::deserialize_hinted(deserializer, visitor)
+    }
+}
+
+struct AsFlexible<T>(FlexConfig, PhantomData<T>);
+
+impl<'de, T: FlexibleParse> Visitor<'de> for AsFlexible<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result { T::expecting(formatter, &self.0) }
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> { T::from_bool(v, &self.0, &self) }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { T::from_i64(v, &self.0, &self) }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { T::from_u64(v, &self.0, &self) }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { T::from_f64(v, &self.0, &self) }
+    fn visit_char<E: Error>(self, v: char) -> Result<Self::Value, E> { T::from_char(v, &self.0, &self) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { T::from_str(v, &self.0, &self) }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> { T::from_bytes(v, &self.0, &self) }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { Err(null_not_allowed(&self)) }
+}
+
+struct AsFlexibleOpt<T>(AsFlexible<T>);
+
+impl<T> AsFlexibleOpt<T> {
+    fn sentinels(&self) -> &'static [&'static str] {
+        self.0.0.sentinels()
+    }
+}
+
+impl<'de, T: FlexibleParse> Visitor<'de> for AsFlexibleOpt<T> {
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("null or ")?;
+        T::expecting(formatter, &self.0.0)?;
+        write_sentinels(formatter, self.sentinels())
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> { T::from_bool(v, &self.0.0, &self).map(Some) }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { T::from_i64(v, &self.0.0, &self).map(Some) }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { T::from_u64(v, &self.0.0, &self).map(Some) }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { T::from_f64(v, &self.0.0, &self).map(Some) }
+    fn visit_char<E: Error>(self, v: char) -> Result<Self::Value, E> { T::from_char(v, &self.0.0, &self).map(Some) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        if is_sentinel(self.sentinels(), self.0.0.trimmed(v)) {
+            return Ok(None);
+        }
+        T::from_str(v, &self.0.0, &self).map(Some)
+    }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        let v = bytes_as_str(v, &self)?;
+        self.visit_str(v)
+    }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { Ok(None) }
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> { Ok(None) }
+}
+
+impl FlexibleParse for bool {
+    fn expecting(formatter: &mut fmt::Formatter<'_>, cfg: &FlexConfig) -> fmt::Result {
+        as_bool::write_expected(formatter, cfg, as_bool::EXPECTED)
+    }
+
+    fn from_bool<E: Error>(v: bool, _: &FlexConfig, _: &dyn Expected) -> Result<Self, E> { Ok(v) }
+    fn from_i64<E: Error>(v: i64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { as_bool::parse_i64(v, cfg, exp) }
+    fn from_u64<E: Error>(v: u64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { as_bool::parse_u64(v, cfg, exp) }
+    fn from_str<E: Error>(v: &str, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { as_bool::parse_str(v, cfg, exp) }
+    fn deserialize_hinted<'de, D: Deserializer<'de>, V: Visitor<'de>>(deserializer: D, visitor: V) -> Result<V::Value, D::Error> {
+        deserializer.deserialize_bool(visitor)
+    }
+}
+
+impl FlexibleParse for char {
+    fn expecting(formatter: &mut fmt::Formatter<'_>, cfg: &FlexConfig) -> fmt::Result {
+        formatter.write_str(as_char::expected(cfg))
+    }
+
+    fn from_char<E: Error>(v: char, _: &FlexConfig, _: &dyn Expected) -> Result<Self, E> { Ok(v) }
+    fn from_i64<E: Error>(v: i64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { as_char::parse_i64(v, cfg, exp) }
+    fn from_u64<E: Error>(v: u64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { as_char::parse_u64(v, cfg, exp) }
+    fn from_str<E: Error>(v: &str, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { as_char::parse_str(v, cfg, exp) }
+    fn deserialize_hinted<'de, D: Deserializer<'de>, V: Visitor<'de>>(deserializer: D, visitor: V) -> Result<V::Value, D::Error> {
+        deserializer.deserialize_char(visitor)
+    }
+}
+
+impl FlexibleParse for f64 {
+    fn expecting(formatter: &mut fmt::Formatter<'_>, cfg: &FlexConfig) -> fmt::Result {
+        formatter.write_str(as_f64::expected(cfg))
+    }
+
+    fn from_i64<E: Error>(v: i64, _: &FlexConfig, _: &dyn Expected) -> Result<Self, E> { as_f64::parse_i64(v) }
+    fn from_u64<E: Error>(v: u64, _: &FlexConfig, _: &dyn Expected) -> Result<Self, E> { as_f64::parse_u64(v) }
+    fn from_f64<E: Error>(v: f64, _: &FlexConfig, _: &dyn Expected) -> Result<Self, E> { Ok(v) }
+    fn from_str<E: Error>(v: &str, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { as_f64::parse_str(v, cfg, exp) }
+    fn deserialize_hinted<'de, D: Deserializer<'de>, V: Visitor<'de>>(deserializer: D, visitor: V) -> Result<V::Value, D::Error> {
+        deserializer.deserialize_f64(visitor)
+    }
+}
+
+impl FlexibleParse for i64 {
+    fn expecting(formatter: &mut fmt::Formatter<'_>, cfg: &FlexConfig) -> fmt::Result {
+        formatter.write_str(as_i64::expected(cfg))
+    }
+
+    fn from_i64<E: Error>(v: i64, _: &FlexConfig, _: &dyn Expected) -> Result<Self, E> { Ok(v) }
+    fn from_u64<E: Error>(v: u64, _: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { as_i64::parse_u64(v, exp) }
+    fn from_f64<E: Error>(v: f64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { as_i64::parse_f64(v, cfg, exp) }
+    fn from_str<E: Error>(v: &str, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { as_i64::parse_str(v, cfg, exp) }
+    fn deserialize_hinted<'de, D: Deserializer<'de>, V: Visitor<'de>>(deserializer: D, visitor: V) -> Result<V::Value, D::Error> {
+        deserializer.deserialize_i64(visitor)
+    }
+}
+
+impl FlexibleParse for u64 {
+    fn expecting(formatter: &mut fmt::Formatter<'_>, cfg: &FlexConfig) -> fmt::Result {
+        formatter.write_str(as_u64::expected(cfg))
+    }
+
+    fn from_i64<E: Error>(v: i64, _: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { as_u64::parse_i64(v, exp) }
+    fn from_u64<E: Error>(v: u64, _: &FlexConfig, _: &dyn Expected) -> Result<Self, E> { Ok(v) }
+    fn from_f64<E: Error>(v: f64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { as_u64::parse_f64(v, cfg, exp) }
+    fn from_str<E: Error>(v: &str, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { as_u64::parse_str(v, cfg, exp) }
+    fn deserialize_hinted<'de, D: Deserializer<'de>, V: Visitor<'de>>(deserializer: D, visitor: V) -> Result<V::Value, D::Error> {
+        deserializer.deserialize_u64(visitor)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{DefaultConfig, Strict};
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq)]
+    struct Score(f32);
+
+    impl FlexibleParse for Score {
+        fn expecting(formatter: &mut fmt::Formatter<'_>, _: &FlexConfig) -> fmt::Result {
+            formatter.write_str("a score between 0 and 1")
+        }
+
+        fn from_i64<E: Error>(v: i64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { Self::from_f64(v as f64, cfg, exp) }
+        fn from_u64<E: Error>(v: u64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> { Self::from_f64(v as f64, cfg, exp) }
+        fn from_f64<E: Error>(v: f64, _: &FlexConfig, exp: &dyn Expected) -> Result<Self, E> {
+            if (0.0..=1.0).contains(&v) {
+                Ok(Score(v as f32))
+            } else {
+                Err(Error::invalid_value(Unexpected::Float(v), exp))
+            }
+        }
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+        #[serde(deserialize_with = "as_flexible")]
+        score: Score,
+        #[serde(default, deserialize_with = "as_flexible_opt")]
+        bonus: Option<Score>,
+    }
+
+    fn parse(json: &str) -> Result<Test, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    #[test]
+    fn test_base_good_parse() {
+        assert_eq!(parse(r#"{"score": 0.5}"#).unwrap(), Test { score: Score(0.5), bonus: None });
+        assert_eq!(parse(r#"{"score": "0.25", "bonus": "1"}"#).unwrap(), Test { score: Score(0.25), bonus: Some(Score(1.0)) });
+        assert_eq!(parse(r#"{"score": 1, "bonus": "none"}"#).unwrap(), Test { score: Score(1.0), bonus: None });
+        assert_eq!(parse(r#"{"score": 0, "bonus": null}"#).unwrap(), Test { score: Score(0.0), bonus: None });
+    }
+
+    #[test]
+    fn test_base_error() {
+        assert!(parse(r#"{"score": 2}"#).is_err());
+        assert!(parse(r#"{"score": "-1"}"#).is_err());
+        assert!(parse(r#"{"score": "high"}"#).is_err());
+        assert!(parse(r#"{"score": true}"#).is_err());
+        assert!(parse(r#"{"score": [0.5]}"#).is_err());
+        assert!(parse(r#"{"score": 0.5, "bonus": "x"}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_error_message() {
+        let err = parse(r#"{"score": 2}"#).unwrap_err().to_string();
+        assert!(err.starts_with("[invalid_format] invalid value: floating point `2.0`, expected a score between 0 and 1"), "{err}");
+        let err = parse(r#"{"score": 0.5, "bonus": true}"#).unwrap_err().to_string();
+        assert!(err.contains(r#"expected null or a score between 0 and 1 (null sentinels: "null""#), "{err}");
+    }
+
+    fn from<T: FlexibleParse, C: Config>(json: &str) -> Result<T, serde_json::Error> {
+        as_flexible_config::<T, C, _>(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn test_builtin_types() {
+        assert_eq!(from::<u64, DefaultConfig>(r#""2.5""#).unwrap(), 3);
+        assert_eq!(from::<i64, DefaultConfig>(r#""-3""#).unwrap(), -3);
+        assert_eq!(from::<f64, DefaultConfig>(r#""1e2""#).unwrap(), 100.0);
+        assert!(from::<bool, DefaultConfig>(r#""yes""#).unwrap());
+        assert_eq!(from::<char, DefaultConfig>("65").unwrap(), 'A');
+        assert!(from::<u64, DefaultConfig>("-1").is_err());
+        assert!(from::<u64, Strict>(r#""1""#).is_err());
+        assert!(from::<bool, Strict>("1").is_err());
+
+        let err = from::<u64, DefaultConfig>("true").unwrap_err().to_string();
+        assert!(err.starts_with("[unexpected_type] invalid type: boolean `true`, expected an unsigned integer or a string"), "{err}");
+        assert_eq!(as_flexible_opt::<bool, _>(&mut serde_json::Deserializer::from_str(r#""none""#)).unwrap(), None);
+    }
+}
@@ -1,55 +1,85 @@
-use crate::deserialize::unexpected::out_or_range_value;
+use crate::config::{Config, FlexConfig, GlobalConfig};
+use crate::deserialize::as_num::as_num;
+use crate::deserialize::lexer::{self, lex, Token};
+use crate::deserialize::wrappers::bytes_as_str;
+use crate::error::{null_not_allowed, out_of_range, precision_loss};
 use serde::de::{Deserializer, Error, Expected, Unexpected, Visitor};
-use std::fmt;
+use core::fmt;
 
 const EXPECTED: &str = "an integer, a float, or a string";
+const STRICT_EXPECTED: &str = "an integer";
 
 pub fn as_i64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
-    deserializer.deserialize_any(AsInt)
+    as_num::<i64, GlobalConfig, D>(deserializer)
+}
+
+/// Like `as_i64`, but with the coercion rules from `C` instead of the global configuration.
+pub fn as_i64_config<'de, C: Config, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    as_num::<i64, C, D>(deserializer)
 }
 
 pub(super) fn parse_u64<E: Error>(v: u64, exp: &dyn Expected) -> Result<i64, E> {
-    i64::try_from(v).map_err(|_| out_or_range_value(Unexpected::Unsigned(v), exp))
+    i64::try_from(v).map_err(|_| out_of_range(Unexpected::Unsigned(v), exp))
 }
 
-pub(super) fn parse_f64<E: Error>(v: f64, exp: &dyn Expected) -> Result<i64, E> {
-    if v < i64::MIN as f64 || v > i64::MAX as f64 {
-        Err(out_or_range_value(Unexpected::Float(v), exp))
+pub(super) fn parse_f64<E: Error>(v: f64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<i64, E> {
+    if !cfg.floats() {
+        Err(Error::invalid_type(Unexpected::Float(v), exp))
+    } else if v.is_nan() {
+        Err(Error::invalid_value(Unexpected::Float(v), exp))
+    } else if v < i64::MIN as f64 || v > i64::MAX as f64 {
+        Err(out_of_range(Unexpected::Float(v), exp))
     } else {
-        Ok(v.round() as i64)
+        cfg.round(v).map(|v| v as i64).ok_or_else(|| precision_loss(Unexpected::Float(v), exp))
     }
 }
 
-pub(super) fn parse_str<E: Error>(v: &str, exp: &dyn Expected) -> Result<i64, E> {
-    match v.parse::<i64>() {
-        Ok(i64) => Ok(i64),
-        _ => match v.parse::<f64>() {
-            Ok(f64) => parse_f64(f64, exp),
-            _ => Err(out_or_range_value(Unexpected::Str(v), exp))
-        }
+pub(super) fn parse_str<E: Error>(v: &str, cfg: &FlexConfig, exp: &dyn Expected) -> Result<i64, E> {
+    let v = cfg.trimmed(v);
+    parse_token(v, lex(v, &[]), cfg, exp)
+}
+
+/// Parses `v`, a string already trimmed and classified as `token`.
+pub(super) fn parse_token<E: Error>(v: &str, token: Token, cfg: &FlexConfig, exp: &dyn Expected) -> Result<i64, E> {
+    if !cfg.strings() {
+        return Err(Error::invalid_type(Unexpected::Str(v), exp));
     }
+    let i64 = match token {
+        Token::Integer { negative: false, magnitude: Some(u64) } => i64::try_from(u64).ok(),
+        Token::Integer { negative: true, magnitude: Some(u64) } => 0i64.checked_sub_unsigned(u64),
+        Token::Integer { magnitude: None, .. } => None,
+        Token::Decimal | Token::Exponent => return parse_f64(lexer::float(v, exp)?, cfg, exp),
+        Token::Word | Token::Sentinel => return Err(Error::invalid_value(Unexpected::Str(v), exp)),
+    };
+    i64.ok_or_else(|| out_of_range(Unexpected::Str(v), exp))
+}
+
+pub(super) fn expected(cfg: &FlexConfig) -> &'static str {
+    if cfg.is_strict() { STRICT_EXPECTED } else { EXPECTED }
 }
 
-struct AsInt;
+pub(super) struct AsInt(pub(super) FlexConfig);
 
 impl<'de> Visitor<'de> for AsInt {
     type Value = i64;
 
     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        formatter.write_str(EXPECTED)
+        formatter.write_str(expected(&self.0))
     }
 
     fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { Ok(v) }
-    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { parse_u64(v, &EXPECTED) }
-    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { parse_f64(v, &EXPECTED) }
-    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { parse_str(v, &EXPECTED) }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { parse_u64(v, &self) }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { parse_f64(v, &self.0, &self) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { parse_str(v, &self.0, &self) }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> { parse_str(bytes_as_str(v, &self)?, &self.0, &self) }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { Err(null_not_allowed(&self)) }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::deserialize::unexpected::OUT_OF_RANGE_ERROR_BEGIN;
+    use crate::error::ErrorKind;
     use serde::Deserialize;
 
     #[derive(Debug, Deserialize, PartialEq)]
@@ -94,8 +124,18 @@ mod tests {
 
     #[test]
     fn test_parse_error_message() {
-        // out fo range error messages must contains OUT_OF_RANGE_ERROR_BEGIN
-        assert!(serde_json::from_str::<Test>(r#"{"int": 12134123412213421411     }"#).unwrap_err().to_string().contains(OUT_OF_RANGE_ERROR_BEGIN)); // u64 -> i64
-        assert!(serde_json::from_str::<Test>(r#"{"int": 121341234122134214120000 }"#).unwrap_err().to_string().contains(OUT_OF_RANGE_ERROR_BEGIN)); // f64 -> i64
+        // out of range errors are recognizable by their kind
+        assert_eq!(ErrorKind::from_message(&serde_json::from_str::<Test>(r#"{"int": 12134123412213421411     }"#).unwrap_err().to_string()), ErrorKind::OutOfRange); // u64 -> i64
+        assert_eq!(ErrorKind::from_message(&serde_json::from_str::<Test>(r#"{"int": 121341234122134214120000 }"#).unwrap_err().to_string()), ErrorKind::OutOfRange); // f64 -> i64
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bytes() {
+        use serde::de::value::{BytesDeserializer, Error};
+
+        assert_eq!(as_i64(BytesDeserializer::<Error>::new(b"-100")).unwrap(), -100);
+        assert_eq!(as_i64(BytesDeserializer::<Error>::new(b"1.12e12")).unwrap(), 1120000000000);
+        assert!(as_i64(BytesDeserializer::<Error>::new(b"12a")).is_err());
+        assert!(as_i64(BytesDeserializer::<Error>::new(b"\xff1")).is_err());
+    }
+}
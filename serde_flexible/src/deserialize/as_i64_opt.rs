@@ -1,24 +1,62 @@
 use super::as_i64;
-use crate::deserialize::wrappers::str_wrap_as_opt;
+use crate::config::{config, Config, FlexConfig, GlobalConfig};
+use crate::deserialize::as_num::as_num_opt;
+use crate::deserialize::hinted::{self, TypeHint};
+use crate::deserialize::wrappers::{bytes_as_str, lex_as_opt};
+use crate::sentinels::{write_expecting, NullSentinels};
 use serde::de::{Deserializer, Error, Visitor};
-use std::fmt;
+use core::fmt;
 
-const EXPECTED: &str = "null, an integer, or a string";
+pub(super) const EXPECTED: &str = "null, an integer, or a string";
+const STRICT_EXPECTED: &str = "null or an integer";
 
 pub fn as_i64_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
-    deserializer.deserialize_any(AsOptInt)
+    as_num_opt::<i64, GlobalConfig, D>(deserializer)
 }
 
-struct AsOptInt;
+/// Like `as_i64_opt`, but with the null sentinels from `S` instead of the global set.
+pub fn as_i64_opt_with<'de, S: NullSentinels, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+    let cfg = config();
+    hinted::deserialize_opt(deserializer, &cfg, TypeHint::I64, AsOptInt::new(cfg, S::sentinels()))
+}
+
+/// Like `as_i64_opt`, but with the coercion rules and null sentinels from `C` instead of the global configuration.
+pub fn as_i64_opt_config<'de, C: Config, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+    as_num_opt::<i64, C, D>(deserializer)
+}
+
+pub(super) struct AsOptInt {
+    cfg: FlexConfig,
+    sentinels: &'static [&'static str],
+}
+
+impl AsOptInt {
+    pub(super) fn new(cfg: FlexConfig, sentinels: &'static [&'static str]) -> Self {
+        AsOptInt { cfg, sentinels }
+    }
+
+    pub(super) fn from_config(cfg: FlexConfig) -> Self {
+        AsOptInt::new(cfg, cfg.sentinels())
+    }
+}
 
 impl<'de> Visitor<'de> for AsOptInt {
     type Value = Option<i64>;
 
-    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result { formatter.write_str(EXPECTED) }
-    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { Ok(v).map(Some) }
-    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { as_i64::parse_u64(v, &EXPECTED).map(Some) }
-    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { as_i64::parse_f64(v, &EXPECTED).map(Some) }
-    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { str_wrap_as_opt(v, &EXPECTED, as_i64::parse_str) }
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_expecting(formatter, if self.cfg.is_strict() { STRICT_EXPECTED } else { EXPECTED }, self.sentinels)
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { Ok(Some(v)) }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { as_i64::parse_u64(v, &self).map(Some) }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { as_i64::parse_f64(v, &self.cfg, &self).map(Some) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        lex_as_opt(self.cfg.trimmed(v), &self, self.sentinels, |v, token, exp| as_i64::parse_token(v, token, &self.cfg, exp))
+    }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        let v = bytes_as_str(v, &self)?;
+        self.visit_str(v)
+    }
     fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { Ok(None) }
 }
 
@@ -26,7 +64,7 @@ impl<'de> Visitor<'de> for AsOptInt {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::deserialize::unexpected::OUT_OF_RANGE_ERROR_BEGIN;
+    use crate::error::ErrorKind;
     use serde::Deserialize;
 
     #[derive(Debug, Deserialize, PartialEq)]
@@ -75,8 +113,17 @@ mod tests {
 
     #[test]
     fn test_parse_error_message() {
-        // out fo range error messages must contains OUT_OF_RANGE_ERROR_BEGIN
-        assert!(serde_json::from_str::<Test>(r#"{"int": 12134123412213421411}"#).unwrap_err().to_string().contains(OUT_OF_RANGE_ERROR_BEGIN)); // u64 -> i64
-        assert!(serde_json::from_str::<Test>(r#"{"int": 121341234122134214120000}"#).unwrap_err().to_string().contains(OUT_OF_RANGE_ERROR_BEGIN)); // f64 -> i64
+        // out of range errors are recognizable by their kind
+        assert_eq!(ErrorKind::from_message(&serde_json::from_str::<Test>(r#"{"int": 12134123412213421411}"#).unwrap_err().to_string()), ErrorKind::OutOfRange); // u64 -> i64
+        assert_eq!(ErrorKind::from_message(&serde_json::from_str::<Test>(r#"{"int": 121341234122134214120000}"#).unwrap_err().to_string()), ErrorKind::OutOfRange); // f64 -> i64
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bytes() {
+        use serde::de::value::{BytesDeserializer, Error};
+
+        assert_eq!(as_i64_opt(BytesDeserializer::<Error>::new(b"-100")).unwrap(), Some(-100));
+        assert_eq!(as_i64_opt(BytesDeserializer::<Error>::new(b"None")).unwrap(), None);
+        assert!(as_i64_opt(BytesDeserializer::<Error>::new(b"\xff1")).is_err());
+    }
+}
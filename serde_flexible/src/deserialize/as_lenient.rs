@@ -0,0 +1,193 @@
+use super::{as_bool_opt, as_char_opt, as_f64_opt, as_i64_opt, as_u64_opt};
+#[cfg(feature = "std")]
+use crate::lenient::{discard, Discarded, Raw};
+use crate::config::config;
+use crate::deserialize::hinted::{self, TypeHint};
+use serde::de::{Deserializer, Error, IgnoredAny, MapAccess, SeqAccess, Unexpected, Visitor};
+use core::fmt;
+
+pub fn as_bool_lenient<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
+    let cfg = config();
+    hinted::deserialize_opt(deserializer, &cfg, TypeHint::Bool, Lenient::new(as_bool_opt::AsOptBool::from_config(cfg), as_bool_opt::EXPECTED))
+}
+
+pub fn as_char_lenient<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<char>, D::Error> {
+    let cfg = config();
+    hinted::deserialize_opt(deserializer, &cfg, TypeHint::Char, Lenient::new(as_char_opt::AsOptChar::from_config(cfg), as_char_opt::EXPECTED))
+}
+
+pub fn as_f64_lenient<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    let cfg = config();
+    hinted::deserialize_opt(deserializer, &cfg, TypeHint::F64, Lenient::new(as_f64_opt::AsOptFloat::from_config(cfg), as_f64_opt::EXPECTED))
+}
+
+pub fn as_i64_lenient<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+    let cfg = config();
+    hinted::deserialize_opt(deserializer, &cfg, TypeHint::I64, Lenient::new(as_i64_opt::AsOptInt::from_config(cfg), as_i64_opt::EXPECTED))
+}
+
+pub fn as_u64_lenient<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    let cfg = config();
+    hinted::deserialize_opt(deserializer, &cfg, TypeHint::U64, Lenient::new(as_u64_opt::AsOptUInt::from_config(cfg), as_u64_opt::EXPECTED))
+}
+
+/// Wraps an `_opt` visitor, replacing its errors with `None` and reporting the dropped input.
+pub(super) struct Lenient<V> {
+    visitor: V,
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    expected: &'static str,
+}
+
+impl<V> Lenient<V> {
+    pub(super) fn new(visitor: V, expected: &'static str) -> Self {
+        Lenient { visitor, expected }
+    }
+
+    fn recover<T, E: Error>(result: Result<Option<T>, E>, report: impl FnOnce(E)) -> Result<Option<T>, E> {
+        result.or_else(|err| {
+            report(err);
+            Ok(None)
+        })
+    }
+}
+
+/// Replaces an error with `None`. The dropped input is only reported with `std`, `$raw` is not
+/// evaluated otherwise.
+macro_rules! recover {
+    ($expected:expr, $result:expr, $raw:expr) => {
+        Self::recover($result, |_err| {
+            #[cfg(feature = "std")]
+            discard(Discarded { raw: $raw, expected: $expected, error: _err.to_string() });
+        })
+    };
+}
+
+impl<'de, T, V: Visitor<'de, Value = Option<T>>> Visitor<'de> for Lenient<V> {
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result { self.visitor.expecting(formatter) }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> {
+        recover!(self.expected, self.visitor.visit_bool(v), Raw::Bool(v))
+    }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> {
+        recover!(self.expected, self.visitor.visit_i64(v), Raw::Signed(v))
+    }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+        recover!(self.expected, self.visitor.visit_u64(v), Raw::Unsigned(v))
+    }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> {
+        recover!(self.expected, self.visitor.visit_f64(v), Raw::Float(v))
+    }
+    fn visit_char<E: Error>(self, v: char) -> Result<Self::Value, E> {
+        recover!(self.expected, self.visitor.visit_char(v), Raw::Char(v))
+    }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        recover!(self.expected, self.visitor.visit_str(v), Raw::Str(v.to_owned()))
+    }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        recover!(self.expected, self.visitor.visit_bytes(v), Raw::Bytes(v.to_owned()))
+    }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { self.visitor.visit_unit() }
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> { self.visitor.visit_unit() }
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_any(self)
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        recover!(self.expected, Err(Error::invalid_type(Unexpected::Seq, &self.visitor)), Raw::Seq)
+    }
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+        recover!(self.expected, Err(Error::invalid_type(Unexpected::Map, &self.visitor)), Raw::Map)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lenient::collect_discarded;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+        #[serde(deserialize_with = "as_f64_lenient")]
+        float: Option<f64>,
+        #[serde(deserialize_with = "as_u64_lenient")]
+        uint: Option<u64>,
+        #[serde(deserialize_with = "as_i64_lenient")]
+        int: Option<i64>,
+        #[serde(deserialize_with = "as_bool_lenient")]
+        bool: Option<bool>,
+        #[serde(deserialize_with = "as_char_lenient")]
+        char: Option<char>,
+    }
+
+    fn parse(v: &str) -> (Test, Vec<Discarded>) {
+        let json = format!(r#"{{"float": {v}, "uint": {v}, "int": {v}, "bool": {v}, "char": {v}}}"#);
+        let (test, discarded) = collect_discarded(|| serde_json::from_str::<Test>(&json));
+        (test.unwrap(), discarded)
+    }
+
+    #[test]
+    fn test_base_good_parse() {
+        let (t, discarded) = parse("1");
+        assert_eq!(t, Test { float: Some(1.0), uint: Some(1), int: Some(1), bool: Some(true), char: Some('\u{1}') });
+        assert!(discarded.is_empty());
+
+        let (t, discarded) = parse(r#""1""#);
+        assert_eq!(t, Test { float: Some(1.0), uint: Some(1), int: Some(1), bool: Some(true), char: Some('1') });
+        assert!(discarded.is_empty());
+
+        // null and sentinels are None, but nothing is discarded
+        for v in ["null", r#""null""#, r#""unknown""#] {
+            let (t, discarded) = parse(v);
+            assert_eq!(t, Test { float: None, uint: None, int: None, bool: None, char: None });
+            assert!(discarded.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_discarded() {
+        let (t, discarded) = parse(r#""abc""#);
+        assert_eq!(t, Test { float: None, uint: None, int: None, bool: None, char: None });
+        assert_eq!(discarded.len(), 5);
+        assert!(discarded.iter().all(|d| d.raw == Raw::Str("abc".to_string())));
+        assert_eq!(discarded[0].expected, as_f64_opt::EXPECTED);
+        assert_eq!(discarded[1].expected, as_u64_opt::EXPECTED);
+        assert!(discarded[0].error.contains(as_f64_opt::EXPECTED));
+
+        let (t, discarded) = parse("-1");
+        assert_eq!(t, Test { float: Some(-1.0), uint: None, int: Some(-1), bool: None, char: None });
+        assert_eq!(discarded.iter().map(|d| &d.raw).collect::<Vec<_>>(), vec![&Raw::Signed(-1); 3]);
+
+        let (t, discarded) = parse(r#"[1, {"a": 2}]"#);
+        assert_eq!(t, Test { float: None, uint: None, int: None, bool: None, char: None });
+        assert!(discarded.iter().all(|d| d.raw == Raw::Seq));
+
+        let (t, discarded) = parse(r#"{"a": [1, 2]}"#);
+        assert_eq!(t.uint, None);
+        assert!(discarded.iter().all(|d| d.raw == Raw::Map));
+
+        let (t, discarded) = parse("true");
+        assert_eq!(t.bool, Some(true));
+        assert_eq!(discarded.len(), 4);
+        assert_eq!(discarded[0].to_string(), format!("discarded boolean `true`, expected {}", as_f64_opt::EXPECTED));
+    }
+
+    #[test]
+    fn test_nested_collect() {
+        let (outer, discarded) = collect_discarded(|| {
+            let (inner, discarded) = collect_discarded(|| as_u64_lenient(serde_json::Value::from("x")).unwrap());
+            assert_eq!(discarded.len(), 1);
+            (inner, as_u64_lenient(serde_json::Value::from("y")).unwrap())
+        });
+        assert_eq!(outer, (None, None));
+        assert_eq!(discarded.len(), 1);
+        assert_eq!(discarded[0].raw, Raw::Str("y".to_string()));
+
+        // nothing is recorded outside of collect_discarded
+        assert_eq!(as_u64_lenient(serde_json::Value::from("z")).unwrap(), None);
+    }
+}
@@ -0,0 +1,232 @@
+use super::as_f64::AsFloat;
+use super::as_f64_opt::AsOptFloat;
+use super::as_i64::AsInt;
+use super::as_i64_opt::AsOptInt;
+use super::as_u64::AsUInt;
+use super::as_u64_opt::AsOptUInt;
+use crate::config::{Config, FlexConfig};
+use crate::deserialize::hinted::{self, TypeHint};
+use crate::error::out_of_range;
+use serde::de::{Deserializer, Error, Unexpected, Visitor};
+use core::fmt;
+use core::marker::PhantomData;
+
+/// Deserializes any [`Num`] type with the coercion rules of the policy `P`.
+///
+/// The value is read like `as_u64`, `as_i64` or `as_f64` and then narrowed to `T`, values that
+/// do not fit are out of range. `as_u64` is `as_num::<u64, GlobalConfig, _>` and `as_u64_config::<C, _>`
+/// is `as_num::<u64, C, _>`, `as_i64` and `as_f64` likewise.
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_flexible::as_num;
+/// use serde_flexible::config::{Lenient, Strict};
+///
+/// #[derive(Deserialize)]
+/// struct Row {
+///     #[serde(deserialize_with = "as_num::<u32, Strict, _>")]
+///     id: u32,
+///     #[serde(deserialize_with = "as_num::<i16, Lenient, _>")]
+///     delta: i16,
+/// }
+///
+/// let row: Row = serde_json::from_str(r#"{"id": 7, "delta": " -2.0 "}"#).unwrap();
+/// assert_eq!((row.id, row.delta), (7, -2));
+/// assert!(serde_json::from_str::<Row>(r#"{"id": "7", "delta": 0}"#).is_err());
+/// assert!(serde_json::from_str::<Row>(r#"{"id": 7, "delta": 40000}"#).is_err());
+/// ```
+pub fn as_num<'de, T: Num, P: Config, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::deserialize_num(deserializer, P::config())
+}
+
+/// Like `as_num`, but reads null and the null sentinels of the policy `P` as `None`.
+/// `as_u64_opt` is `as_num_opt::<u64, GlobalConfig, _>`, `as_i64_opt` and `as_f64_opt` likewise.
+pub fn as_num_opt<'de, T: Num, P: Config, D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error> {
+    T::deserialize_num_opt(deserializer, P::config())
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// The number types of [`as_num`]: `u8` to `u64`, `usize`, `i8` to `i64`, `isize`, `f32` and `f64`.
+pub trait Num: Sized + private::Sealed {
+    #[doc(hidden)]
+    fn deserialize_num<'de, D: Deserializer<'de>>(deserializer: D, cfg: FlexConfig) -> Result<Self, D::Error>;
+
+    #[doc(hidden)]
+    fn deserialize_num_opt<'de, D: Deserializer<'de>>(deserializer: D, cfg: FlexConfig) -> Result<Option<Self>, D::Error>;
+}
+
+/// Conversion from the value of the `u64`, `i64` or `f64` visitor.
+trait Narrow<W>: Sized {
+    fn narrow<E: Error>(v: W) -> Result<Self, E>;
+}
+
+impl<W, T: Narrow<W>> Narrow<Option<W>> for Option<T> {
+    fn narrow<E: Error>(v: Option<W>) -> Result<Self, E> {
+        v.map(T::narrow).transpose()
+    }
+}
+
+macro_rules! impl_num {
+    ($wide:ty, $unexpected:ident, $visitor:ident, $opt_visitor:ident => $($ty:ty: $hint:ident),+) => {
+        $(
+            impl private::Sealed for $ty {}
+
+            impl Narrow<$wide> for $ty {
+                fn narrow<E: Error>(v: $wide) -> Result<Self, E> {
+                    <$ty>::try_from(v).map_err(|_| out_of_range(Unexpected::$unexpected(v), &concat!("a value fitting in ", stringify!($ty))))
+                }
+            }
+
+            impl Num for $ty {
+                fn deserialize_num<'de, D: Deserializer<'de>>(deserializer: D, cfg: FlexConfig) -> Result<Self, D::Error> {
+                    hinted::deserialize(deserializer, &cfg, TypeHint::$hint, Narrowing::new($visitor(cfg)))
+                }
+
+                fn deserialize_num_opt<'de, D: Deserializer<'de>>(deserializer: D, cfg: FlexConfig) -> Result<Option<Self>, D::Error> {
+                    hinted::deserialize_opt(deserializer, &cfg, TypeHint::$hint, Narrowing::new($opt_visitor::from_config(cfg)))
+                }
+            }
+        )+
+    };
+}
+
+impl_num!(u64, Unsigned, AsUInt, AsOptUInt => u8: U8, u16: U16, u32: U32, usize: U64);
+impl_num!(i64, Signed, AsInt, AsOptInt => i8: I8, i16: I16, i32: I32, isize: I64);
+
+/// The types read by their visitor as is.
+macro_rules! impl_wide {
+    ($($ty:ty: $hint:ident, $visitor:ident, $opt_visitor:ident),+) => {
+        $(
+            impl private::Sealed for $ty {}
+
+            impl Num for $ty {
+                fn deserialize_num<'de, D: Deserializer<'de>>(deserializer: D, cfg: FlexConfig) -> Result<Self, D::Error> {
+                    hinted::deserialize(deserializer, &cfg, TypeHint::$hint, $visitor(cfg))
+                }
+
+                fn deserialize_num_opt<'de, D: Deserializer<'de>>(deserializer: D, cfg: FlexConfig) -> Result<Option<Self>, D::Error> {
+                    hinted::deserialize_opt(deserializer, &cfg, TypeHint::$hint, $opt_visitor::from_config(cfg))
+                }
+            }
+        )+
+    };
+}
+
+impl_wide!(u64: U64, AsUInt, AsOptUInt, i64: I64, AsInt, AsOptInt, f64: F64, AsFloat, AsOptFloat);
+
+impl private::Sealed for f32 {}
+
+impl Narrow<f64> for f32 {
+    fn narrow<E: Error>(v: f64) -> Result<Self, E> {
+        if v.is_finite() && (v as f32).is_infinite() {
+            Err(out_of_range(Unexpected::Float(v), &"a value fitting in f32"))
+        } else {
+            Ok(v as f32)
+        }
+    }
+}
+
+impl Num for f32 {
+    fn deserialize_num<'de, D: Deserializer<'de>>(deserializer: D, cfg: FlexConfig) -> Result<Self, D::Error> {
+        hinted::deserialize(deserializer, &cfg, TypeHint::F32, Narrowing::new(AsFloat(cfg)))
+    }
+
+    fn deserialize_num_opt<'de, D: Deserializer<'de>>(deserializer: D, cfg: FlexConfig) -> Result<Option<Self>, D::Error> {
+        hinted::deserialize_opt(deserializer, &cfg, TypeHint::F32, Narrowing::new(AsOptFloat::from_config(cfg)))
+    }
+}
+
+/// Wraps a `u64`, `i64` or `f64` visitor, narrowing its value to `T`.
+struct Narrowing<V, T> {
+    visitor: V,
+    marker: PhantomData<T>,
+}
+
+impl<V, T> Narrowing<V, T> {
+    fn new(visitor: V) -> Self {
+        Narrowing { visitor, marker: PhantomData }
+    }
+}
+
+impl<'de, V: Visitor<'de>, T: Narrow<V::Value>> Visitor<'de> for Narrowing<V, T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> { self.visitor.visit_bool(v).and_then(T::narrow) }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { self.visitor.visit_i64(v).and_then(T::narrow) }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { self.visitor.visit_u64(v).and_then(T::narrow) }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { self.visitor.visit_f64(v).and_then(T::narrow) }
+    fn visit_char<E: Error>(self, v: char) -> Result<Self::Value, E> { self.visitor.visit_char(v).and_then(T::narrow) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { self.visitor.visit_str(v).and_then(T::narrow) }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> { self.visitor.visit_bytes(v).and_then(T::narrow) }
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> { self.visitor.visit_none().and_then(T::narrow) }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { self.visitor.visit_unit().and_then(T::narrow) }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{DefaultConfig, Lenient, Strict};
+    use crate::error::ErrorKind;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+        #[serde(deserialize_with = "as_num::<u8, DefaultConfig, _>")]
+        byte: u8,
+        #[serde(deserialize_with = "as_num::<i16, DefaultConfig, _>")]
+        short: i16,
+        #[serde(deserialize_with = "as_num::<f32, DefaultConfig, _>")]
+        float: f32,
+        #[serde(deserialize_with = "as_num_opt::<u32, DefaultConfig, _>")]
+        opt: Option<u32>,
+    }
+
+    fn parse(byte: &str, short: &str, float: &str, opt: &str) -> Result<Test, serde_json::Error> {
+        serde_json::from_str(&format!(r#"{{"byte": {byte}, "short": {short}, "float": {float}, "opt": {opt}}}"#))
+    }
+
+    #[test]
+    fn test_base_good_parse() {
+        assert_eq!(parse("255", "-32768", "1.5", "null").unwrap(), Test { byte: 255, short: -32768, float: 1.5, opt: None });
+        assert_eq!(parse(r#""1""#, r#""-2.5""#, r#""3""#, r#""4""#).unwrap(), Test { byte: 1, short: -3, float: 3.0, opt: Some(4) });
+        assert_eq!(parse("1.4", "7", "1", r#""none""#).unwrap(), Test { byte: 1, short: 7, float: 1.0, opt: None });
+    }
+
+    #[test]
+    fn test_base_error() {
+        let out_of_range = |json: Result<Test, serde_json::Error>| ErrorKind::from_message(&json.unwrap_err().to_string()) == ErrorKind::OutOfRange;
+        assert!(out_of_range(parse("256", "0", "0", "0")));
+        assert!(out_of_range(parse("-1", "0", "0", "0")));
+        assert!(out_of_range(parse("0", "32768", "0", "0")));
+        assert!(out_of_range(parse("0", r#""-40000""#, "0", "0")));
+        assert!(out_of_range(parse("0", "0", "0", "4294967296")));
+        assert!(out_of_range(parse("0", "0", "1e300", "0")));
+        assert!(parse(r#""x""#, "0", "0", "0").is_err());
+        assert!(parse("0", "0", "0", "true").is_err());
+    }
+
+    #[test]
+    fn test_policies() {
+        let mut de = serde_json::Deserializer::from_str(r#""1""#);
+        assert!(as_num::<i8, Strict, _>(&mut de).is_err());
+        let mut de = serde_json::Deserializer::from_str("1.5");
+        assert!(as_num::<u16, Strict, _>(&mut de).is_err());
+        let mut de = serde_json::Deserializer::from_str("-3");
+        assert_eq!(as_num::<i32, Strict, _>(&mut de).unwrap(), -3);
+
+        let mut de = serde_json::Deserializer::from_str(r#"" 12 ""#);
+        assert_eq!(as_num::<usize, Lenient, _>(&mut de).unwrap(), 12);
+        let mut de = serde_json::Deserializer::from_str(r#"" none ""#);
+        assert_eq!(as_num_opt::<isize, Lenient, _>(&mut de).unwrap(), None);
+        let mut de = serde_json::Deserializer::from_str(r#"" 12 ""#);
+        assert!(as_num::<usize, DefaultConfig, _>(&mut de).is_err());
+    }
+}
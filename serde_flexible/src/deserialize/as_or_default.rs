@@ -0,0 +1,94 @@
+use super::as_lenient::{as_bool_lenient, as_f64_lenient, as_i64_lenient, as_u64_lenient};
+use serde::de::Deserializer;
+
+pub fn as_bool_or_default<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    as_bool_lenient(deserializer).map(Option::unwrap_or_default)
+}
+
+pub fn as_f64_or_default<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    as_f64_lenient(deserializer).map(Option::unwrap_or_default)
+}
+
+pub fn as_i64_or_default<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    as_i64_lenient(deserializer).map(Option::unwrap_or_default)
+}
+
+pub fn as_u64_or_default<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    as_u64_lenient(deserializer).map(Option::unwrap_or_default)
+}
+
+pub fn as_bool_or<'de, const V: bool, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    as_bool_lenient(deserializer).map(|v| v.unwrap_or(V))
+}
+
+pub fn as_i64_or<'de, const V: i64, D: Deserializer<'de>>(deserializer: D) -> Result<i64, D::Error> {
+    as_i64_lenient(deserializer).map(|v| v.unwrap_or(V))
+}
+
+pub fn as_u64_or<'de, const V: u64, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    as_u64_lenient(deserializer).map(|v| v.unwrap_or(V))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lenient::{collect_discarded, Raw};
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+        #[serde(deserialize_with = "as_u64_or_default")]
+        retries: u64,
+        #[serde(deserialize_with = "as_u64_or::<3, _>")]
+        attempts: u64,
+        #[serde(deserialize_with = "as_i64_or_default")]
+        offset: i64,
+        #[serde(deserialize_with = "as_i64_or::<-1, _>")]
+        limit: i64,
+        #[serde(deserialize_with = "as_f64_or_default")]
+        ratio: f64,
+        #[serde(deserialize_with = "as_bool_or_default")]
+        enabled: bool,
+        #[serde(deserialize_with = "as_bool_or::<true, _>")]
+        visible: bool,
+    }
+
+    fn parse(v: &str) -> Test {
+        serde_json::from_str::<Test>(&format!(
+            r#"{{"retries": {v}, "attempts": {v}, "offset": {v}, "limit": {v}, "ratio": {v}, "enabled": {v}, "visible": {v}}}"#
+        )).unwrap()
+    }
+
+    const FALLBACK: Test = Test { retries: 0, attempts: 3, offset: 0, limit: -1, ratio: 0.0, enabled: false, visible: true };
+
+    #[test]
+    fn test_base_good_parse() {
+        assert_eq!(parse("1"), Test { retries: 1, attempts: 1, offset: 1, limit: 1, ratio: 1.0, enabled: true, visible: true });
+        assert_eq!(parse(r#""0""#), Test { retries: 0, attempts: 0, offset: 0, limit: 0, ratio: 0.0, enabled: false, visible: false });
+    }
+
+    #[test]
+    fn test_fallback() {
+        assert_eq!(parse("null"), FALLBACK);
+        assert_eq!(parse(r#""none""#), FALLBACK);
+        assert_eq!(parse(r#""abc""#), FALLBACK);
+        assert_eq!(parse("[1, 2]"), FALLBACK);
+
+        let t = parse("-5");
+        assert_eq!((t.retries, t.attempts, t.offset, t.limit, t.ratio), (0, 3, -5, -5, -5.0));
+        assert_eq!((t.enabled, t.visible), (false, true));
+    }
+
+    #[test]
+    fn test_fallback_is_reported() {
+        let (t, discarded) = collect_discarded(|| parse(r#""abc""#));
+        assert_eq!(t, FALLBACK);
+        assert_eq!(discarded.len(), 7);
+        assert!(discarded.iter().all(|d| d.raw == Raw::Str("abc".to_string())));
+
+        // null is a value of its own and falls back silently
+        let (_, discarded) = collect_discarded(|| parse("null"));
+        assert!(discarded.is_empty());
+    }
+}
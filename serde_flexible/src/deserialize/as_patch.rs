@@ -0,0 +1,132 @@
+use super::as_bool_opt::as_bool_opt;
+use super::as_char_opt::as_char_opt;
+use super::as_f64_opt::as_f64_opt;
+use super::as_i64_opt::as_i64_opt;
+#[cfg(feature = "alloc")]
+use super::as_string_opt::as_string_opt_only_sentinels;
+use super::as_u64_opt::as_u64_opt;
+use crate::patch::Patch;
+use serde::de::Deserializer;
+
+pub fn as_bool_patch<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Patch<bool>, D::Error> {
+    as_bool_opt(deserializer).map(Patch::from)
+}
+
+pub fn as_char_patch<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Patch<char>, D::Error> {
+    as_char_opt(deserializer).map(Patch::from)
+}
+
+pub fn as_f64_patch<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Patch<f64>, D::Error> {
+    as_f64_opt(deserializer).map(Patch::from)
+}
+
+pub fn as_i64_patch<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Patch<i64>, D::Error> {
+    as_i64_opt(deserializer).map(Patch::from)
+}
+
+/// Only `null` and the null sentinels clear the field, `""` and blank strings are values.
+#[cfg(feature = "alloc")]
+pub fn as_string_patch<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Patch<alloc::string::String>, D::Error> {
+    as_string_opt_only_sentinels(deserializer).map(Patch::from)
+}
+
+pub fn as_u64_patch<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Patch<u64>, D::Error> {
+    as_u64_opt(deserializer).map(Patch::from)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+        #[serde(default, deserialize_with = "as_u64_patch")]
+        uint: Patch<u64>,
+        #[serde(default, deserialize_with = "as_i64_patch")]
+        int: Patch<i64>,
+        #[serde(default, deserialize_with = "as_f64_patch")]
+        float: Patch<f64>,
+        #[serde(default, deserialize_with = "as_bool_patch")]
+        bool: Patch<bool>,
+        #[serde(default, deserialize_with = "as_char_patch")]
+        char: Patch<char>,
+        #[serde(default, deserialize_with = "as_string_patch")]
+        str: Patch<String>,
+    }
+
+    #[test]
+    fn test_missing() {
+        let t = serde_json::from_str::<Test>(r#"{}"#).unwrap();
+        assert_eq!(t, Test { uint: Patch::Missing, int: Patch::Missing, float: Patch::Missing, bool: Patch::Missing, char: Patch::Missing, str: Patch::Missing });
+    }
+
+    #[test]
+    fn test_null() {
+        for v in ["null", r#""null""#, r#""NULL""#, r#""none""#] {
+            let t = serde_json::from_str::<Test>(&format!(
+                r#"{{"uint": {v}, "int": {v}, "float": {v}, "bool": {v}, "char": {v}, "str": {v}}}"#
+            )).unwrap();
+            assert_eq!(t, Test { uint: Patch::Null, int: Patch::Null, float: Patch::Null, bool: Patch::Null, char: Patch::Null, str: Patch::Null }, "{v}");
+        }
+    }
+
+    #[test]
+    fn test_value() {
+        let t = serde_json::from_str::<Test>(r#"{"uint": "1", "int": -2, "float": "2.5", "bool": "yes", "char": "x", "str": 12}"#).unwrap();
+        assert_eq!(t, Test {
+            uint: Patch::Value(1),
+            int: Patch::Value(-2),
+            float: Patch::Value(2.5),
+            bool: Patch::Value(true),
+            char: Patch::Value('x'),
+            str: Patch::Value("12".to_string()),
+        });
+    }
+
+    #[test]
+    fn test_empty_string() {
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": ""}"#).unwrap().str, Patch::Value(String::new()));
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": " "}"#).unwrap().str, Patch::Value(" ".to_string()));
+    }
+
+    #[test]
+    fn test_base_error() {
+        assert!(serde_json::from_str::<Test>(r#"{"uint": "abc"}"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"bool": 2}"#).is_err());
+    }
+
+    #[test]
+    fn test_serialize() {
+        #[derive(serde::Serialize)]
+        struct Out {
+            #[serde(skip_serializing_if = "Patch::is_missing")]
+            age: Patch<u64>,
+        }
+
+        let json = |age| serde_json::to_string(&Out { age });
+        assert_eq!(json(Patch::Value(5)).unwrap(), r#"{"age":5}"#);
+        assert_eq!(json(Patch::Null).unwrap(), r#"{"age":null}"#);
+        assert_eq!(json(Patch::Missing).unwrap(), "{}");
+        assert!(serde_json::to_string(&Patch::<u64>::Missing).is_err());
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(Option::<Option<u64>>::from(Patch::Missing), None);
+        assert_eq!(Option::<Option<u64>>::from(Patch::Null), Some(None));
+        assert_eq!(Option::<Option<u64>>::from(Patch::Value(1)), Some(Some(1)));
+        assert_eq!(Patch::from(Some(1)), Patch::Value(1));
+        assert_eq!(Patch::<u64>::from(None), Patch::Null);
+        assert_eq!(Patch::Value(2).map(|v| v * 2), Patch::Value(4));
+
+        let mut target = Some(1);
+        Patch::Missing.apply_to(&mut target);
+        assert_eq!(target, Some(1));
+        Patch::Value(2).apply_to(&mut target);
+        assert_eq!(target, Some(2));
+        Patch::Null.apply_to(&mut target);
+        assert_eq!(target, None);
+    }
+}
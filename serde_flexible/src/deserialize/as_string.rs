@@ -1,13 +1,27 @@
-use serde::de::{Deserializer, Error, Visitor};
-use std::fmt;
+use crate::config::config;
+use crate::deserialize::hinted::{self, TypeHint};
+use crate::deserialize::wrappers::bytes_as_str;
+use crate::error::null_not_allowed;
+use serde::de::{Deserializer, Error, Unexpected, Visitor};
+use core::fmt;
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 
-const EXPECTED: &str = "a string, bool, or a number";
+const EXPECTED: &str = "a string, bool, a number, or UTF-8 bytes";
 
 pub fn as_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
-    deserializer.deserialize_any(AsString)
+    hinted::deserialize(deserializer, &config(), TypeHint::String, AsString { lossy: false })
 }
 
-struct AsString;
+/// Like `as_string`, but invalid UTF-8 in byte strings is replaced with `U+FFFD` instead of failing.
+pub fn as_string_lossy<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    hinted::deserialize(deserializer, &config(), TypeHint::String, AsString { lossy: true })
+}
+
+struct AsString {
+    lossy: bool,
+}
 
 impl<'de> Visitor<'de> for AsString {
     type Value = String;
@@ -31,6 +45,22 @@ impl<'de> Visitor<'de> for AsString {
     fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
         Ok(v.to_owned())
     }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        match self.lossy {
+            true => Ok(String::from_utf8_lossy(v).into_owned()),
+            false => bytes_as_str(v, &EXPECTED).map(str::to_owned),
+        }
+    }
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        match String::from_utf8(v) {
+            Ok(v) => Ok(v),
+            Err(err) if self.lossy => Ok(String::from_utf8_lossy(err.as_bytes()).into_owned()),
+            Err(err) => Err(Error::invalid_value(Unexpected::Bytes(err.as_bytes()), &EXPECTED)),
+        }
+    }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        Err(null_not_allowed(&self))
+    }
 }
 
 
@@ -64,4 +94,20 @@ mod tests {
         assert!(serde_json::from_str::<Test>(r#"{"str": null         }"#).is_err());
         assert!(serde_json::from_str::<Test>(r#"{"str": ["hello"]]   }"#).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bytes() {
+        use serde::de::value::{BytesDeserializer, Error};
+
+        assert_eq!(as_string(BytesDeserializer::<Error>::new(b"hello")).unwrap(), "hello");
+        assert_eq!(as_string(BytesDeserializer::<Error>::new("héllo".as_bytes())).unwrap(), "héllo");
+        assert!(as_string(BytesDeserializer::<Error>::new(b"he\xffllo")).unwrap_err().to_string().contains(EXPECTED));
+
+        assert_eq!(as_string_lossy(BytesDeserializer::<Error>::new(b"hello")).unwrap(), "hello");
+        assert_eq!(as_string_lossy(BytesDeserializer::<Error>::new(b"he\xffllo")).unwrap(), "he\u{fffd}llo");
+
+        assert_eq!(AsString { lossy: false }.visit_byte_buf::<Error>(b"hello".to_vec()).unwrap(), "hello");
+        assert!(AsString { lossy: false }.visit_byte_buf::<Error>(b"he\xffllo".to_vec()).is_err());
+        assert_eq!(AsString { lossy: true }.visit_byte_buf::<Error>(b"he\xffllo".to_vec()).unwrap(), "he\u{fffd}llo");
+    }
+}
@@ -0,0 +1,84 @@
+use super::{as_string, as_string_opt};
+use crate::normalize::Normalize;
+use serde::de::Deserializer;
+use alloc::string::String;
+
+pub fn as_string_normalized<'de, N: Normalize, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    as_string::as_string(deserializer).map(N::normalize)
+}
+
+pub fn as_string_normalized_opt<'de, N: Normalize, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    as_string_opt::as_string_opt(deserializer).map(|v| v.map(N::normalize))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::normalize::{Lowercase, Trim, Uppercase};
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test {
+        #[serde(deserialize_with = "as_string_normalized::<(Trim, Lowercase), _>")]
+        email: String,
+        #[serde(deserialize_with = "as_string_normalized_opt::<(Trim, Uppercase), _>")]
+        sku: Option<String>,
+    }
+
+    #[test]
+    fn test_base_good_parse() {
+        let t = serde_json::from_str::<Test>(r#"{"email": "  John.Doe@Example.COM ", "sku": " ab-12 "}"#).unwrap();
+        assert_eq!(t.email, "john.doe@example.com");
+        assert_eq!(t.sku.unwrap(), "AB-12");
+
+        let t = serde_json::from_str::<Test>(r#"{"email": 42, "sku": null}"#).unwrap();
+        assert_eq!(t.email, "42");
+        assert!(t.sku.is_none());
+
+        let t = serde_json::from_str::<Test>(r#"{"email": true, "sku": 1.5}"#).unwrap();
+        assert_eq!(t.email, "true");
+        assert_eq!(t.sku.unwrap(), "1.5");
+    }
+
+    #[test]
+    fn test_order() {
+        assert_eq!(<(Trim, Lowercase)>::normalize(" ÄB ".to_string()), "äb");
+        assert_eq!(<()>::normalize(" x ".to_string()), " x ");
+        assert_eq!(<(Lowercase, Uppercase)>::normalize("aB".to_string()), "AB");
+        assert_eq!(<(Uppercase, Lowercase)>::normalize("aB".to_string()), "ab");
+    }
+
+    #[test]
+    fn test_lowercase_is_not_case_folding() {
+        assert_eq!(Lowercase::normalize("Straße".to_string()), "straße");
+        assert_eq!(Lowercase::normalize("STRASSE".to_string()), "strasse");
+        assert_eq!(Lowercase::normalize("ΟΔΟΣ".to_string()), "οδος");
+    }
+
+    #[cfg(feature = "unicode")]
+    #[test]
+    fn test_unicode_forms() {
+        use crate::normalize::{Nfc, Nfd, Nfkc};
+
+        // "é" as a single code point and as "e" + combining acute accent
+        let composed = "\u{e9}";
+        let decomposed = "e\u{301}";
+
+        assert_eq!(Nfc::normalize(decomposed.to_string()), composed);
+        assert_eq!(Nfc::normalize(composed.to_string()), composed);
+        assert_eq!(Nfd::normalize(composed.to_string()), decomposed);
+        assert_eq!(Nfkc::normalize("\u{fb01}le".to_string()), "file"); // "fi" ligature
+        assert_eq!(<(Trim, Nfkc, Lowercase)>::normalize(" \u{FF21}BC ".to_string()), "abc"); // fullwidth "A"
+
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct User {
+            #[serde(deserialize_with = "as_string_normalized::<(Trim, Lowercase, Nfc), _>")]
+            name: String,
+        }
+
+        let a = serde_json::from_str::<User>(r#"{"name": "José"}"#).unwrap();
+        let b = serde_json::from_str::<User>(r#"{"name": " JOSÉ "}"#).unwrap();
+        assert_eq!(a, b);
+    }
+}
@@ -1,19 +1,81 @@
+use crate::config::config;
+use crate::deserialize::hinted::{self, TypeHint};
+use crate::deserialize::wrappers::bytes_as_str;
+use crate::sentinels::{is_sentinel, write_expecting, GlobalSentinels, NullSentinels};
 use serde::de::{Deserializer, Error, Visitor};
-use std::fmt;
+use core::fmt;
+use core::marker::PhantomData;
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
 
-const EXPECTED: &str = "null, a string, bool, or a number";
+const EXPECTED: &str = "null, a string, bool, a number, or UTF-8 bytes";
+
+/// Which string inputs are deserialized as `None`. Every level up to `Sentinel` also includes the previous ones.
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+enum NoneIf {
+    /// Only `null` is `None`, all strings are kept as is.
+    Null,
+    /// Empty strings are `None`.
+    Empty,
+    /// Empty and whitespace-only strings are `None`.
+    Blank,
+    /// Blank strings and null sentinels are `None`.
+    Sentinel,
+    /// Only null sentinels are `None`, empty and blank strings are kept.
+    OnlySentinels,
+}
 
 pub fn as_string_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
-    deserializer.deserialize_any(AsOptString)
+    hinted::deserialize_opt(deserializer, &config(), TypeHint::String, AsOptString::<GlobalSentinels>(NoneIf::Null, PhantomData))
+}
+
+/// Like `as_string_opt`, but `""` is deserialized as `None`.
+pub fn as_string_opt_non_empty<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    hinted::deserialize_opt(deserializer, &config(), TypeHint::String, AsOptString::<GlobalSentinels>(NoneIf::Empty, PhantomData))
+}
+
+/// Like `as_string_opt`, but empty and whitespace-only strings are deserialized as `None`.
+pub fn as_string_opt_non_blank<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    hinted::deserialize_opt(deserializer, &config(), TypeHint::String, AsOptString::<GlobalSentinels>(NoneIf::Blank, PhantomData))
 }
 
-struct AsOptString;
+/// Like `as_string_opt_non_blank`, but the global null sentinels used by the other `_opt`
+/// deserializers (`"null"`, `"none"`, `"unknown"` by default) are `None` as well.
+pub fn as_string_opt_sentinel<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    as_string_opt_with::<GlobalSentinels, D>(deserializer)
+}
+
+/// Like `as_string_opt_sentinel`, but with the null sentinels from `S` instead of the global set.
+pub fn as_string_opt_with<'de, S: NullSentinels, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    hinted::deserialize_opt(deserializer, &config(), TypeHint::String, AsOptString::<S>(NoneIf::Sentinel, PhantomData))
+}
+
+/// Like `as_string_opt`, but the global null sentinels are `None` as well, for `as_string_patch`.
+pub(super) fn as_string_opt_only_sentinels<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    deserializer.deserialize_any(AsOptString::<GlobalSentinels>(NoneIf::OnlySentinels, PhantomData))
+}
 
-impl<'de> Visitor<'de> for AsOptString {
+struct AsOptString<S>(NoneIf, PhantomData<S>);
+
+impl<S: NullSentinels> AsOptString<S> {
+    fn is_none(&self, v: &str) -> bool {
+        if self.0 == NoneIf::OnlySentinels {
+            return is_sentinel(S::sentinels(), v);
+        }
+        (self.0 >= NoneIf::Empty && v.is_empty())
+            || (self.0 >= NoneIf::Blank && v.trim().is_empty())
+            || (self.0 >= NoneIf::Sentinel && is_sentinel(S::sentinels(), v))
+    }
+}
+
+impl<'de, S: NullSentinels> Visitor<'de> for AsOptString<S> {
     type Value = Option<String>;
 
     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        formatter.write_str(EXPECTED)
+        match self.0 {
+            NoneIf::Sentinel | NoneIf::OnlySentinels => write_expecting(formatter, EXPECTED, S::sentinels()),
+            _ => formatter.write_str(EXPECTED),
+        }
     }
 
     fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> {
@@ -29,7 +91,15 @@ impl<'de> Visitor<'de> for AsOptString {
         Ok(Some(v.to_string()))
     }
     fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
-        Ok(Some(v.to_owned()))
+        if self.is_none(v) {
+            Ok(None)
+        } else {
+            Ok(Some(v.to_owned()))
+        }
+    }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        let v = bytes_as_str(v, &self)?;
+        self.visit_str(v)
     }
     fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
         Ok(None)
@@ -76,4 +146,80 @@ mod tests {
     fn test_base_error() {
         assert!(serde_json::from_str::<Test>(r#"{"str": ["hello"]]   }"#).is_err());
     }
-}
\ No newline at end of file
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct TestOptions {
+        #[serde(deserialize_with = "as_string_opt_non_empty")]
+        non_empty: Option<String>,
+        #[serde(deserialize_with = "as_string_opt_non_blank")]
+        non_blank: Option<String>,
+        #[serde(deserialize_with = "as_string_opt_sentinel")]
+        sentinel: Option<String>,
+    }
+
+    fn parse_options(v: &str) -> TestOptions {
+        serde_json::from_str::<TestOptions>(&format!(r#"{{"non_empty": {v}, "non_blank": {v}, "sentinel": {v}}}"#)).unwrap()
+    }
+
+    #[test]
+    fn test_bytes() {
+        use serde::de::value::{BytesDeserializer, Error};
+
+        assert_eq!(as_string_opt(BytesDeserializer::<Error>::new(b"hello")).unwrap().unwrap(), "hello");
+        assert_eq!(as_string_opt(BytesDeserializer::<Error>::new(b"")).unwrap().unwrap(), "");
+        assert!(as_string_opt_non_empty(BytesDeserializer::<Error>::new(b"")).unwrap().is_none());
+        assert!(as_string_opt_sentinel(BytesDeserializer::<Error>::new(b"NULL")).unwrap().is_none());
+        assert!(as_string_opt(BytesDeserializer::<Error>::new(b"\xff")).is_err());
+    }
+
+    #[test]
+    fn test_none_options() {
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": ""        }"#).unwrap().str.unwrap(), "");
+        assert_eq!(serde_json::from_str::<Test>(r#"{"str": "   "     }"#).unwrap().str.unwrap(), "   ");
+
+        let t = parse_options(r#""""#);
+        assert_eq!((t.non_empty, t.non_blank, t.sentinel), (None, None, None));
+
+        let t = parse_options(r#"" \t ""#);
+        assert_eq!(t.non_empty.unwrap(), " \t ");
+        assert_eq!((t.non_blank, t.sentinel), (None, None));
+
+        for sentinel in [r#""null""#, r#""NULL""#, r#""none""#, r#""Unknown""#] {
+            let t = parse_options(sentinel);
+            assert!(t.non_empty.is_some());
+            assert!(t.non_blank.is_some());
+            assert!(t.sentinel.is_none());
+        }
+
+        let t = parse_options("null");
+        assert_eq!((t.non_empty, t.non_blank, t.sentinel), (None, None, None));
+
+        let t = parse_options(r#"" hello ""#);
+        assert_eq!(t.non_empty.unwrap(), " hello ");
+        assert_eq!(t.non_blank.unwrap(), " hello ");
+        assert_eq!(t.sentinel.unwrap(), " hello ");
+
+        let t = parse_options("0");
+        assert_eq!(t.sentinel.unwrap(), "0");
+    }
+
+    struct Dashes;
+
+    impl NullSentinels for Dashes {
+        fn sentinels() -> &'static [&'static str] { &["-", "--"] }
+    }
+
+    #[test]
+    fn test_custom_sentinels() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct TestWith {
+            #[serde(deserialize_with = "as_string_opt_with::<Dashes, _>")]
+            str: Option<String>,
+        }
+
+        assert!(serde_json::from_str::<TestWith>(r#"{"str": "--"   }"#).unwrap().str.is_none());
+        assert!(serde_json::from_str::<TestWith>(r#"{"str": " "    }"#).unwrap().str.is_none());
+        assert_eq!(serde_json::from_str::<TestWith>(r#"{"str": "null" }"#).unwrap().str.unwrap(), "null");
+        assert_eq!(serde_json::from_str::<TestWith>(r#"{"str": "---"  }"#).unwrap().str.unwrap(), "---");
+    }
+}
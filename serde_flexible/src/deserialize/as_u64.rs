@@ -1,55 +1,84 @@
-use crate::deserialize::unexpected::out_or_range_value;
+use crate::config::{Config, FlexConfig, GlobalConfig};
+use crate::deserialize::as_num::as_num;
+use crate::deserialize::lexer::{self, lex, Token};
+use crate::deserialize::wrappers::bytes_as_str;
+use crate::error::{null_not_allowed, out_of_range, precision_loss};
 use serde::de::{Deserializer, Error, Expected, Unexpected, Visitor};
-use std::fmt;
+use core::fmt;
 
 const EXPECTED: &str = "an unsigned integer or a string";
+const STRICT_EXPECTED: &str = "an unsigned integer";
 
 pub fn as_u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
-    deserializer.deserialize_any(AsUInt)
+    as_num::<u64, GlobalConfig, D>(deserializer)
+}
+
+/// Like `as_u64`, but with the coercion rules from `C` instead of the global configuration.
+pub fn as_u64_config<'de, C: Config, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    as_num::<u64, C, D>(deserializer)
 }
 
 pub(super) fn parse_i64<E: Error>(v: i64, exp: &dyn Expected) -> Result<u64, E> {
-    u64::try_from(v).map_err(|_| out_or_range_value(Unexpected::Signed(v), exp))
+    u64::try_from(v).map_err(|_| out_of_range(Unexpected::Signed(v), exp))
 }
 
-pub(super) fn parse_f64<E: Error>(v: f64, exp: &dyn Expected) -> Result<u64, E> {
-    if v < u64::MIN as f64 || v > u64::MAX as f64 {
-        Err(out_or_range_value(Unexpected::Float(v), exp))
+pub(super) fn parse_f64<E: Error>(v: f64, cfg: &FlexConfig, exp: &dyn Expected) -> Result<u64, E> {
+    if !cfg.floats() {
+        Err(Error::invalid_type(Unexpected::Float(v), exp))
+    } else if v.is_nan() {
+        Err(Error::invalid_value(Unexpected::Float(v), exp))
+    } else if v < u64::MIN as f64 || v > u64::MAX as f64 {
+        Err(out_of_range(Unexpected::Float(v), exp))
     } else {
-        Ok(v.round() as u64)
+        cfg.round(v).map(|v| v as u64).ok_or_else(|| precision_loss(Unexpected::Float(v), exp))
     }
 }
 
-pub(super) fn parse_str<E: Error>(v: &str, exp: &dyn Expected) -> Result<u64, E> {
-    match v.parse::<u64>() {
-        Ok(u64) => Ok(u64),
-        _ => match v.parse::<f64>() {
-            Ok(f64) => parse_f64(f64, exp),
-            _ => Err(out_or_range_value(Unexpected::Str(v), exp))
-        }
+pub(super) fn parse_str<E: Error>(v: &str, cfg: &FlexConfig, exp: &dyn Expected) -> Result<u64, E> {
+    let v = cfg.trimmed(v);
+    parse_token(v, lex(v, &[]), cfg, exp)
+}
+
+/// Parses `v`, a string already trimmed and classified as `token`.
+pub(super) fn parse_token<E: Error>(v: &str, token: Token, cfg: &FlexConfig, exp: &dyn Expected) -> Result<u64, E> {
+    if !cfg.strings() {
+        return Err(Error::invalid_type(Unexpected::Str(v), exp));
+    }
+    match token {
+        Token::Integer { negative: false, magnitude: Some(u64) } => Ok(u64),
+        Token::Integer { negative: true, magnitude: Some(0) } => Ok(0),
+        Token::Integer { .. } => Err(out_of_range(Unexpected::Str(v), exp)),
+        Token::Decimal | Token::Exponent => parse_f64(lexer::float(v, exp)?, cfg, exp),
+        Token::Word | Token::Sentinel => Err(Error::invalid_value(Unexpected::Str(v), exp)),
     }
 }
 
-struct AsUInt;
+pub(super) fn expected(cfg: &FlexConfig) -> &'static str {
+    if cfg.is_strict() { STRICT_EXPECTED } else { EXPECTED }
+}
+
+pub(super) struct AsUInt(pub(super) FlexConfig);
 
 impl<'de> Visitor<'de> for AsUInt {
     type Value = u64;
 
     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        formatter.write_str(EXPECTED)
+        formatter.write_str(expected(&self.0))
     }
 
-    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { parse_i64(v, &EXPECTED) }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { parse_i64(v, &self) }
     fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { Ok(v) }
-    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { parse_f64(v, &EXPECTED) }
-    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { parse_str(v, &EXPECTED) }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { parse_f64(v, &self.0, &self) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { parse_str(v, &self.0, &self) }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> { parse_str(bytes_as_str(v, &self)?, &self.0, &self) }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { Err(null_not_allowed(&self)) }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::deserialize::unexpected::OUT_OF_RANGE_ERROR_BEGIN;
+    use crate::error::ErrorKind;
     use serde::Deserialize;
 
     #[derive(Debug, Deserialize, PartialEq)]
@@ -92,8 +121,66 @@ mod tests {
 
     #[test]
     fn test_parse_error_message() {
-        // out fo range error messages must contains OUT_OF_RANGE_ERROR_BEGIN
-        assert!(serde_json::from_str::<Test>(r#"{"int": -1     }"#).unwrap_err().to_string().contains(OUT_OF_RANGE_ERROR_BEGIN)); // i64 -> u64
-        assert!(serde_json::from_str::<Test>(r#"{"int": 121341234122134214120000 }"#).unwrap_err().to_string().contains(OUT_OF_RANGE_ERROR_BEGIN)); // f64 -> u64
+        // out of range errors are recognizable by their kind
+        assert_eq!(ErrorKind::from_message(&serde_json::from_str::<Test>(r#"{"int": -1     }"#).unwrap_err().to_string()), ErrorKind::OutOfRange); // i64 -> u64
+        assert_eq!(ErrorKind::from_message(&serde_json::from_str::<Test>(r#"{"int": 121341234122134214120000 }"#).unwrap_err().to_string()), ErrorKind::OutOfRange); // f64 -> u64
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bytes() {
+        use serde::de::value::{BytesDeserializer, Error};
+
+        assert_eq!(as_u64(BytesDeserializer::<Error>::new(b"100")).unwrap(), 100);
+        assert_eq!(as_u64(BytesDeserializer::<Error>::new(b"100.5")).unwrap(), 101);
+        assert!(as_u64(BytesDeserializer::<Error>::new(b"-1")).is_err());
+        assert!(as_u64(BytesDeserializer::<Error>::new(b"\xff1")).is_err());
+        let err = as_u64(BytesDeserializer::<Error>::new(b"x")).unwrap_err().to_string();
+        assert!(err.ends_with("expected an unsigned integer or a string"), "{err}");
+    }
+
+    #[test]
+    fn test_config() {
+        use crate::config::{Config, FlexConfig, Rounding};
+
+        struct Floor;
+        impl Config for Floor {
+            fn config() -> FlexConfig { FlexConfig::new().rounding(Rounding::Floor).trim(true) }
+        }
+
+        struct Exact;
+        impl Config for Exact {
+            fn config() -> FlexConfig { FlexConfig::new().rounding(Rounding::Reject) }
+        }
+
+        struct NoCoercion;
+        impl Config for NoCoercion {
+            fn config() -> FlexConfig { FlexConfig::new().accept_floats(false).accept_strings(false) }
+        }
+
+        struct Strict;
+        impl Config for Strict {
+            fn config() -> FlexConfig { FlexConfig::new().strict(true) }
+        }
+
+        fn parse<C: Config>(json: &str) -> Result<u64, serde_json::Error> {
+            as_u64_config::<C, _>(&mut serde_json::Deserializer::from_str(json))
+        }
+
+        assert_eq!(parse::<Floor>("100.9").unwrap(), 100);
+        assert_eq!(parse::<Floor>(r#"" 100.9 ""#).unwrap(), 100);
+        assert!(parse::<Floor>("-0.5").is_err());
+
+        assert_eq!(parse::<Exact>("100.0").unwrap(), 100);
+        assert_eq!(parse::<Exact>(r#""1e3""#).unwrap(), 1000);
+        assert!(parse::<Exact>("100.5").is_err());
+        assert!(parse::<Exact>(r#"" 100""#).is_err());
+
+        assert_eq!(parse::<NoCoercion>("100").unwrap(), 100);
+        assert!(parse::<NoCoercion>("100.0").is_err());
+        assert!(parse::<NoCoercion>(r#""100""#).is_err());
+
+        assert_eq!(parse::<Strict>("100").unwrap(), 100);
+        assert!(parse::<Strict>("1.0").unwrap_err().to_string().contains("invalid type: floating point `1.0`, expected an unsigned integer"));
+        assert!(parse::<Strict>(r#""1""#).unwrap_err().to_string().contains("invalid type: string \"1\", expected an unsigned integer"));
+    }
+}
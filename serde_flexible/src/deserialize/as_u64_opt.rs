@@ -1,38 +1,70 @@
-use super::{as_u64};
+use super::as_u64;
+use crate::config::{config, Config, FlexConfig, GlobalConfig};
+use crate::deserialize::as_num::as_num_opt;
+use crate::deserialize::hinted::{self, TypeHint};
+use crate::deserialize::wrappers::{bytes_as_str, lex_as_opt};
+use crate::sentinels::{write_expecting, NullSentinels};
 use serde::de::{Deserializer, Error, Visitor};
-use std::fmt;
-use crate::deserialize::wrappers::str_wrap_as_opt;
+use core::fmt;
 
-const EXPECTED: &str = "null, an unsigned integer, or a string";
+pub(super) const EXPECTED: &str = "null, an unsigned integer, or a string";
+const STRICT_EXPECTED: &str = "null or an unsigned integer";
 
 pub fn as_u64_opt<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
-    deserializer.deserialize_any(AsOptUInt)
+    as_num_opt::<u64, GlobalConfig, D>(deserializer)
 }
 
-struct AsOptUInt;
+/// Like `as_u64_opt`, but with the null sentinels from `S` instead of the global set.
+pub fn as_u64_opt_with<'de, S: NullSentinels, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    let cfg = config();
+    hinted::deserialize_opt(deserializer, &cfg, TypeHint::U64, AsOptUInt::new(cfg, S::sentinels()))
+}
+
+/// Like `as_u64_opt`, but with the coercion rules and null sentinels from `C` instead of the global configuration.
+pub fn as_u64_opt_config<'de, C: Config, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    as_num_opt::<u64, C, D>(deserializer)
+}
+
+pub(super) struct AsOptUInt {
+    cfg: FlexConfig,
+    sentinels: &'static [&'static str],
+}
+
+impl AsOptUInt {
+    pub(super) fn new(cfg: FlexConfig, sentinels: &'static [&'static str]) -> Self {
+        AsOptUInt { cfg, sentinels }
+    }
+
+    pub(super) fn from_config(cfg: FlexConfig) -> Self {
+        AsOptUInt::new(cfg, cfg.sentinels())
+    }
+}
 
 impl<'de> Visitor<'de> for AsOptUInt {
     type Value = Option<u64>;
 
     fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        formatter.write_str(EXPECTED)
+        write_expecting(formatter, if self.cfg.is_strict() { STRICT_EXPECTED } else { EXPECTED }, self.sentinels)
     }
 
-    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { as_u64::parse_i64(v, &EXPECTED).map(Some) }
-    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { Ok(v).map(Some) }
-    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { as_u64::parse_f64(v, &EXPECTED).map(Some) }
-    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { str_wrap_as_opt(v, &EXPECTED, as_u64::parse_str) }
-    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
-        Ok(None)
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { as_u64::parse_i64(v, &self).map(Some) }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { Ok(Some(v)) }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { as_u64::parse_f64(v, &self.cfg, &self).map(Some) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        lex_as_opt(self.cfg.trimmed(v), &self, self.sentinels, |v, token, exp| as_u64::parse_token(v, token, &self.cfg, exp))
+    }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        let v = bytes_as_str(v, &self)?;
+        self.visit_str(v)
     }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { Ok(None) }
 }
 
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::deserialize::unexpected::OUT_OF_RANGE_ERROR_BEGIN;
+    use crate::error::ErrorKind;
     use serde::Deserialize;
 
     #[derive(Debug, Deserialize, PartialEq)]
@@ -82,8 +114,71 @@ mod tests {
 
     #[test]
     fn test_parse_error_message() {
-        // out fo range error messages must contains OUT_OF_RANGE_ERROR_BEGIN
-        assert!(serde_json::from_str::<Test>(r#"{"int": -1     }"#).unwrap_err().to_string().contains(OUT_OF_RANGE_ERROR_BEGIN)); // i64 -> u64
-        assert!(serde_json::from_str::<Test>(r#"{"int": 121341234122134214120000 }"#).unwrap_err().to_string().contains(OUT_OF_RANGE_ERROR_BEGIN)); // f64 -> u64
+        // out of range errors are recognizable by their kind
+        assert_eq!(ErrorKind::from_message(&serde_json::from_str::<Test>(r#"{"int": -1     }"#).unwrap_err().to_string()), ErrorKind::OutOfRange); // i64 -> u64
+        assert_eq!(ErrorKind::from_message(&serde_json::from_str::<Test>(r#"{"int": 121341234122134214120000 }"#).unwrap_err().to_string()), ErrorKind::OutOfRange); // f64 -> u64
+    }
+
+    #[test]
+    fn test_bytes() {
+        use serde::de::value::{BytesDeserializer, Error};
+
+        assert_eq!(as_u64_opt(BytesDeserializer::<Error>::new(b"100")).unwrap(), Some(100));
+        assert_eq!(as_u64_opt(BytesDeserializer::<Error>::new(b"null")).unwrap(), None);
+        assert!(as_u64_opt(BytesDeserializer::<Error>::new(b"\xff1")).is_err());
     }
-}
\ No newline at end of file
+
+    struct Feed;
+
+    impl NullSentinels for Feed {
+        fn sentinels() -> &'static [&'static str] { &["N/A", "-", "nil", "\\N", "#N/A", ""] }
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct TestWith {
+        #[serde(deserialize_with = "as_u64_opt_with::<Feed, _>")]
+        int: Option<u64>,
+    }
+
+    #[test]
+    fn test_custom_sentinels() {
+        for v in [r#""N/A""#, r#""n/a""#, r#""-""#, r#""NIL""#, r#""\\N""#, r##""#N/A""##, r#""""#, "null"] {
+            assert!(serde_json::from_str::<TestWith>(&format!(r#"{{"int": {v}}}"#)).unwrap().int.is_none(), "{v}");
+        }
+        assert_eq!(serde_json::from_str::<TestWith>(r#"{"int": "12"}"#).unwrap().int, Some(12));
+
+        // the default sentinels are not part of the custom set
+        assert!(serde_json::from_str::<TestWith>(r#"{"int": "none"}"#).is_err());
+        assert!(serde_json::from_str::<Test>(r#"{"int": "N/A"}"#).is_err());
+    }
+
+    #[test]
+    fn test_sentinels_in_error_message() {
+        let err = serde_json::from_str::<TestWith>(r#"{"int": "abc"}"#).unwrap_err().to_string();
+        assert!(err.contains(EXPECTED), "{err}");
+        assert!(err.contains(r##"(null sentinels: "N/A", "-", "nil", "\\N", "#N/A", "")"##), "{err}");
+    }
+
+    #[test]
+    fn test_config() {
+        use crate::config::{Config, FlexConfig};
+        use crate::sentinels::NullSentinels;
+
+        struct Dash;
+        impl Config for Dash {
+            fn config() -> FlexConfig { FlexConfig::new().null_sentinels(&["-"]).trim(true) }
+        }
+        impl NullSentinels for Dash {
+            fn sentinels() -> &'static [&'static str] { &["n/a"] }
+        }
+
+        let parse = |json: &str| as_u64_opt_config::<Dash, _>(&mut serde_json::Deserializer::from_str(json));
+        assert_eq!(parse(r#"" - ""#).unwrap(), None);
+        assert_eq!(parse(r#"" 7 ""#).unwrap(), Some(7));
+        assert!(parse(r#""none""#).unwrap_err().to_string().contains(r#"(null sentinels: "-")"#));
+
+        let parse = |json: &str| as_u64_opt_with::<Dash, _>(&mut serde_json::Deserializer::from_str(json));
+        assert_eq!(parse(r#""N/A""#).unwrap(), None);
+        assert!(parse(r#""-""#).is_err());
+    }
+}
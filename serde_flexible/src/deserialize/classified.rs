@@ -0,0 +1,126 @@
+use crate::error::{invalid_format, unexpected_type};
+use serde::de::{DeserializeSeed, Deserializer, EnumAccess, Error, Expected, MapAccess, SeqAccess, StdError, Unexpected, Visitor};
+use core::fmt;
+
+/// Passes values through to `V` with an error type that adds the code of its kind to `invalid_type`
+/// and `invalid_value` errors, including the ones of serde's default `visit_*` methods.
+pub(super) struct Classified<V>(pub(super) V);
+
+/// The error type `V` sees, see [`Classified`].
+struct Marked<E>(E);
+
+impl<E: fmt::Debug> fmt::Debug for Marked<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Marked<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<E: Error> StdError for Marked<E> {}
+
+impl<E: Error> Error for Marked<E> {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Marked(E::custom(msg))
+    }
+
+    fn invalid_type(unexp: Unexpected, exp: &dyn Expected) -> Self {
+        Marked(unexpected_type(unexp, exp))
+    }
+
+    fn invalid_value(unexp: Unexpected, exp: &dyn Expected) -> Self {
+        Marked(invalid_format(unexp, exp))
+    }
+}
+
+macro_rules! forward {
+    ($($method:ident($ty:ty)),+ $(,)?) => {
+        $(
+            fn $method<E: Error>(self, v: $ty) -> Result<Self::Value, E> {
+                self.0.$method::<Marked<E>>(v).map_err(|err| err.0)
+            }
+        )+
+    };
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Classified<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    forward! {
+        visit_bool(bool),
+        visit_i8(i8), visit_i16(i16), visit_i32(i32), visit_i64(i64), visit_i128(i128),
+        visit_u8(u8), visit_u16(u16), visit_u32(u32), visit_u64(u64), visit_u128(u128),
+        visit_f32(f32), visit_f64(f64), visit_char(char),
+        visit_str(&str), visit_borrowed_str(&'de str),
+        visit_bytes(&[u8]), visit_borrowed_bytes(&'de [u8]),
+    }
+
+    #[cfg(feature = "alloc")]
+    forward! {
+        visit_string(alloc::string::String),
+        visit_byte_buf(alloc::vec::Vec<u8>),
+    }
+
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+        self.0.visit_none::<Marked<E>>().map_err(|err| err.0)
+    }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+        self.0.visit_unit::<Marked<E>>().map_err(|err| err.0)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.visit_some(deserializer)
+    }
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.visit_newtype_struct(deserializer)
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_seq(Access(seq)).map_err(|err| err.0)
+    }
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_map(Access(map)).map_err(|err| err.0)
+    }
+    // none of the visitors read enums
+    fn visit_enum<A: EnumAccess<'de>>(self, _: A) -> Result<Self::Value, A::Error> {
+        Err(unexpected_type(Unexpected::Enum, &self))
+    }
+}
+
+/// A sequence or map with the error type of [`Classified`].
+struct Access<A>(A);
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for Access<A> {
+    type Error = Marked<A::Error>;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        self.0.next_element_seed(seed).map_err(Marked)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for Access<A> {
+    type Error = Marked<A::Error>;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        self.0.next_key_seed(seed).map_err(Marked)
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, Self::Error> {
+        self.0.next_value_seed(seed).map_err(Marked)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
@@ -0,0 +1,104 @@
+use super::classified::Classified;
+use crate::config::FlexConfig;
+use serde::de::{Deserializer, Visitor};
+use core::fmt;
+
+/// Calls the `deserialize_*` method of a target type, for formats that are not self-describing.
+pub(super) trait Hint: Copy {
+    fn deserialize<'de, D: Deserializer<'de>, V: Visitor<'de>>(self, deserializer: D, visitor: V) -> Result<V::Value, D::Error>;
+}
+
+#[derive(Clone, Copy)]
+pub(super) enum TypeHint {
+    Bool,
+    Char,
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    F32,
+    F64,
+    #[cfg(feature = "alloc")]
+    Str,
+    #[cfg(feature = "alloc")]
+    String,
+}
+
+impl Hint for TypeHint {
+    fn deserialize<'de, D: Deserializer<'de>, V: Visitor<'de>>(self, deserializer: D, visitor: V) -> Result<V::Value, D::Error> {
+        match self {
+            TypeHint::Bool => deserializer.deserialize_bool(visitor),
+            TypeHint::Char => deserializer.deserialize_char(visitor),
+            TypeHint::I8 => deserializer.deserialize_i8(visitor),
+            TypeHint::I16 => deserializer.deserialize_i16(visitor),
+            TypeHint::I32 => deserializer.deserialize_i32(visitor),
+            TypeHint::I64 => deserializer.deserialize_i64(visitor),
+            TypeHint::U8 => deserializer.deserialize_u8(visitor),
+            TypeHint::U16 => deserializer.deserialize_u16(visitor),
+            TypeHint::U32 => deserializer.deserialize_u32(visitor),
+            TypeHint::U64 => deserializer.deserialize_u64(visitor),
+            TypeHint::F32 => deserializer.deserialize_f32(visitor),
+            TypeHint::F64 => deserializer.deserialize_f64(visitor),
+            #[cfg(feature = "alloc")]
+            TypeHint::Str => deserializer.deserialize_str(visitor),
+            #[cfg(feature = "alloc")]
+            TypeHint::String => deserializer.deserialize_string(visitor),
+        }
+    }
+}
+
+/// Deserializes with `hint` if `cfg` asks for type hints, and with `deserialize_any` otherwise.
+/// The errors of `visitor` are [`Classified`].
+pub(super) fn deserialize<'de, D, H, V>(deserializer: D, cfg: &FlexConfig, hint: H, visitor: V) -> Result<V::Value, D::Error>
+where
+    D: Deserializer<'de>,
+    H: Hint,
+    V: Visitor<'de>,
+{
+    let visitor = Classified(visitor);
+    if cfg.hinted() {
+        hint.deserialize(deserializer, visitor)
+    } else {
+        deserializer.deserialize_any(visitor)
+    }
+}
+
+/// Like `deserialize`, for `_opt` visitors. With type hints the value is read with `deserialize_option`,
+/// `None` is passed to `visit_unit` of the visitor and `Some` is deserialized with `hint`.
+pub(super) fn deserialize_opt<'de, D, H, V>(deserializer: D, cfg: &FlexConfig, hint: H, visitor: V) -> Result<V::Value, D::Error>
+where
+    D: Deserializer<'de>,
+    H: Hint,
+    V: Visitor<'de>,
+{
+    let visitor = Classified(visitor);
+    if cfg.hinted() {
+        deserializer.deserialize_option(HintedOption { hint, visitor })
+    } else {
+        deserializer.deserialize_any(visitor)
+    }
+}
+
+struct HintedOption<H, V> {
+    hint: H,
+    visitor: V,
+}
+
+impl<'de, H: Hint, V: Visitor<'de>> Visitor<'de> for HintedOption<H, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> { self.visitor.visit_unit() }
+    fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> { self.visitor.visit_unit() }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.hint.deserialize(deserializer, self.visitor)
+    }
+}
@@ -0,0 +1,149 @@
+use crate::sentinels::is_sentinel;
+use serde::de::{Error, Expected, Unexpected};
+
+/// The shape of a scalar string, found in a single pass without allocating.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Token {
+    /// `[+-]?[0-9]+`, with its magnitude, `None` if it doesn't fit in a `u64`.
+    Integer { negative: bool, magnitude: Option<u64> },
+    /// A number with a fraction and no exponent: `1.5`, `.5` or `5.`.
+    Decimal,
+    /// A number with an exponent: `1e5` or `-1.5E-3`.
+    Exponent,
+    /// One of the null sentinels.
+    Sentinel,
+    /// Anything else, including the empty string, `inf` and `NaN`.
+    Word,
+}
+
+/// Classifies `v`. The sentinels are matched first, so numeric sentinels like `"-1"` work.
+/// `Decimal` and `Exponent` are exactly the numbers `str::parse::<f64>` accepts.
+#[inline]
+pub(super) fn lex(v: &str, sentinels: &[&str]) -> Token {
+    if is_sentinel(sentinels, v) {
+        return Token::Sentinel;
+    }
+    let bytes = v.as_bytes();
+    let (negative, mut i) = match bytes.first() {
+        Some(b'-') => (true, 1),
+        Some(b'+') => (false, 1),
+        _ => (false, 0),
+    };
+
+    let start = i;
+    let mut wrapped = 0u64;
+    for digit in bytes[i..].iter().map(|b| b.wrapping_sub(b'0')) {
+        if digit > 9 {
+            break;
+        }
+        wrapped = wrapped.wrapping_mul(10).wrapping_add(digit as u64);
+        i += 1;
+    }
+    let mut digits = i - start;
+    if i == bytes.len() {
+        if digits == 0 {
+            return Token::Word;
+        }
+        // up to 19 digits can't overflow
+        let magnitude = if digits < 20 { Some(wrapped) } else { checked_magnitude(&bytes[start..]) };
+        return Token::Integer { negative, magnitude };
+    }
+
+    let mut token = Token::Word;
+    if bytes[i] == b'.' {
+        i += 1;
+        let start = i;
+        i += count_digits(&bytes[i..]);
+        digits += i - start;
+        token = Token::Decimal;
+    }
+    if digits == 0 {
+        return Token::Word;
+    }
+    if let Some(b'e' | b'E') = bytes.get(i) {
+        i += 1;
+        if let Some(b'-' | b'+') = bytes.get(i) {
+            i += 1;
+        }
+        let exponent = count_digits(&bytes[i..]);
+        if exponent == 0 {
+            return Token::Word;
+        }
+        i += exponent;
+        token = Token::Exponent;
+    }
+    if i == bytes.len() { token } else { Token::Word }
+}
+
+/// The value of a number token, for `Decimal`, `Exponent` and `Integer` tokens without a magnitude.
+/// Any other string is an invalid value.
+pub(super) fn float<E: Error>(v: &str, exp: &dyn Expected) -> Result<f64, E> {
+    v.parse().map_err(|_| E::invalid_value(Unexpected::Str(v), exp))
+}
+
+fn checked_magnitude(digits: &[u8]) -> Option<u64> {
+    digits.iter().try_fold(0u64, |m, d| m.checked_mul(10)?.checked_add((d - b'0') as u64))
+}
+
+fn count_digits(bytes: &[u8]) -> usize {
+    bytes.iter().take_while(|b| b.is_ascii_digit()).count()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn integer(negative: bool, magnitude: u64) -> Token {
+        Token::Integer { negative, magnitude: Some(magnitude) }
+    }
+
+    #[test]
+    fn test_lex() {
+        assert_eq!(lex("0", &[]), integer(false, 0));
+        assert_eq!(lex("123", &[]), integer(false, 123));
+        assert_eq!(lex("+123", &[]), integer(false, 123));
+        assert_eq!(lex("-123", &[]), integer(true, 123));
+        assert_eq!(lex("007", &[]), integer(false, 7));
+        assert_eq!(lex("18446744073709551615", &[]), integer(false, u64::MAX));
+        assert_eq!(lex("18446744073709551616", &[]), Token::Integer { negative: false, magnitude: None });
+        assert_eq!(lex("-99999999999999999999", &[]), Token::Integer { negative: true, magnitude: None });
+
+        for v in ["1.5", ".5", "5.", "-0.0", "+1.25"] {
+            assert_eq!(lex(v, &[]), Token::Decimal, "{v}");
+        }
+        for v in ["1e5", "1E5", "1.5e-3", "-.5e+2", "5.e1", "1e0001"] {
+            assert_eq!(lex(v, &[]), Token::Exponent, "{v}");
+        }
+        for v in ["", "-", "+", ".", "-.", "e5", ".e5", "1e", "1e+", "1.2.3", "123a", "a123", "1 2", " 1", "1_000", "0x10", "inf", "NaN", "--1", "1e5.0"] {
+            assert_eq!(lex(v, &[]), Token::Word, "{v}");
+        }
+    }
+
+    #[test]
+    fn test_same_as_parse() {
+        // `Decimal` and `Exponent` are accepted by the float parser, everything else is a word or an integer
+        for v in ["1.5", ".5", "5.", "1e5", "-.5e+2", "5.e1", "", ".", "e5", "1e", "1.2.3", "+-1", "1e5.0"] {
+            let float = matches!(lex(v, &[]), Token::Decimal | Token::Exponent | Token::Integer { .. });
+            assert_eq!(float, v.parse::<f64>().is_ok(), "{v}");
+        }
+    }
+
+    #[test]
+    fn test_float() {
+        let float = |v| float::<serde::de::value::Error>(v, &"a float");
+        assert_eq!(float("-1.5e2").unwrap(), -150.0);
+        assert_eq!(float("18446744073709551616").unwrap(), 18446744073709551616.0);
+        assert_eq!(float("1.2.3").unwrap_err().to_string(), r#"invalid value: string "1.2.3", expected a float"#);
+    }
+
+    #[test]
+    fn test_sentinels() {
+        let sentinels = &["null", "-1", ""];
+        assert_eq!(lex("NULL", sentinels), Token::Sentinel);
+        assert_eq!(lex("-1", sentinels), Token::Sentinel);
+        assert_eq!(lex("", sentinels), Token::Sentinel);
+        assert_eq!(lex("-2", sentinels), integer(true, 2));
+        assert_eq!(lex("nil", sentinels), Token::Word);
+    }
+}
@@ -1,23 +1,42 @@
+use super::lexer::{lex, Token};
+use crate::sentinels::is_sentinel;
 use serde::de::{Unexpected, Error, Expected};
 
+/// Reads a byte string as a string. Byte strings count as strings, so the expectations of the
+/// deserializers, e.g. "an unsigned integer or a string", include them.
+pub(super) fn bytes_as_str<'a, E: Error>(v: &'a [u8], exp: &dyn Expected) -> Result<&'a str, E> {
+    core::str::from_utf8(v).map_err(|_| Error::invalid_value(Unexpected::Bytes(v), exp))
+}
+
 pub(super) fn str_wrap_as_opt<T, E, F>(
     v: &str,
     exp: &dyn Expected,
+    sentinels: &[&str],
     parser: F,
 ) -> Result<Option<T>, E>
 where
     F: Fn(&str, &dyn Expected) -> Result<T, E>,
     E: Error
 {
-    match v {
-        "null" => Ok(None),
-        _ =>
-            match parser(v, exp) {
-                Ok(f) => Ok(Some(f)),
-                Err(_) => match v.to_lowercase().as_str() {
-                    "null" | "none" | "unknown" => Ok(None),
-                    _ => Err(Error::invalid_value(Unexpected::Str(v), exp)),
-                }
-            }
+    if is_sentinel(sentinels, v) {
+        return Ok(None);
+    }
+    parser(v, exp).map(Some)
+}
+
+/// Like `str_wrap_as_opt`, for number parsers. The string is classified once, sentinels included.
+pub(super) fn lex_as_opt<T, E, F>(
+    v: &str,
+    exp: &dyn Expected,
+    sentinels: &[&str],
+    parser: F,
+) -> Result<Option<T>, E>
+where
+    F: Fn(&str, Token, &dyn Expected) -> Result<T, E>,
+    E: Error
+{
+    match lex(v, sentinels) {
+        Token::Sentinel => Ok(None),
+        token => parser(v, token, exp).map(Some),
     }
-}
\ No newline at end of file
+}
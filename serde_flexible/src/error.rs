@@ -0,0 +1,350 @@
+//! Error kinds, and [`FlexError`] to inspect them.
+//!
+//! The deserializers report errors through the error type of the format, e.g. `serde_json::Error`.
+//! Every error of this crate starts with the stable code of its [`ErrorKind`] in brackets, followed by
+//! the input and the expectation: ``[out_of_range] out of range value: integer `-1`, expected an unsigned integer``.
+//! [`ErrorKind::from_message`] and [`FlexError::from_message`] read them back from any error, and
+//! classify messages without a code, e.g. from serde itself, as [`ErrorKind::Other`]:
+//!
+//! ```
+//! use serde::Deserialize;
+//! use serde_flexible::as_u64;
+//! use serde_flexible::error::{ErrorKind, FlexError};
+//!
+//! #[derive(Debug, Deserialize)]
+//! struct Row {
+//!     #[serde(deserialize_with = "as_u64")]
+//!     count: u64,
+//! }
+//!
+//! let err = serde_json::from_str::<Row>(r#"{"count": -1}"#).unwrap_err();
+//! assert_eq!(ErrorKind::from_message(&err.to_string()), ErrorKind::OutOfRange);
+//!
+//! let err = FlexError::from_message(&err.to_string());
+//! assert_eq!((err.code(), err.input()), ("out_of_range", "integer `-1`"));
+//! ```
+//!
+//! `FlexError` is a [`serde::de::Error`] as well. Used as the error type of a deserializer,
+//! e.g. `serde::de::value::U64Deserializer<FlexError>`, it is returned as is and carries the
+//! kind without parsing. Prefer that where the deserializer's error type can be chosen.
+//!
+//! # Message format
+//!
+//! Most formats only keep the message of an error, so the message format is a stability
+//! contract, covered by semver like the rest of the API:
+//!
+//! ```text
+//! [<code>] <phrase>[: <input>], expected <expectation>
+//! ```
+//!
+//! - `<code>` is [`ErrorKind::code`] and `<phrase>` the fixed description of the kind:
+//!   `invalid value`, `out of range value`, `invalid type`, `null not allowed` or `precision loss`.
+//!   Both stay the same for a kind, new kinds get new codes.
+//! - `<input>` is the input as serde's [`Unexpected`] writes it, e.g. ``integer `-1` ``. It is
+//!   left out for `null not allowed`.
+//! - `<expectation>` is the expectation of the deserializer. Its wording is not part of the
+//!   contract.
+//!
+//! Formats may put a prefix ending in `: ` before the message, e.g. a field path, and add a
+//! suffix, e.g. ` at line 1 column 5`. Both are kept in [`FlexError::input`] and
+//! [`FlexError::expected`] as they can't be told apart from the message.
+
+use core::fmt;
+use serde::de::{Error, Expected, Unexpected};
+
+/// What went wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// A string or value that can't be read as the target type, e.g. `"123a"` for a number.
+    InvalidFormat,
+    /// A number outside of the range of the target type, e.g. `-1` for a `u64`.
+    OutOfRange,
+    /// An input of a type that is not accepted, e.g. a map for a number, or a string in strict mode.
+    UnexpectedType,
+    /// `null` for a deserializer that is not optional.
+    NullNotAllowed,
+    /// A float that is not integral, with [`Rounding::Reject`](crate::config::Rounding::Reject).
+    PrecisionLoss,
+    /// An error that does not come from this crate, e.g. a missing field or a plain `u64` field given a string.
+    Other,
+}
+
+const KINDS: [ErrorKind; 5] = [
+    ErrorKind::InvalidFormat,
+    ErrorKind::OutOfRange,
+    ErrorKind::UnexpectedType,
+    ErrorKind::NullNotAllowed,
+    ErrorKind::PrecisionLoss,
+];
+
+impl ErrorKind {
+    /// A stable identifier, e.g. `"out_of_range"`, for API responses and metrics.
+    /// Messages of this crate start with it in brackets.
+    pub const fn code(self) -> &'static str {
+        match self {
+            ErrorKind::InvalidFormat => "invalid_format",
+            ErrorKind::OutOfRange => "out_of_range",
+            ErrorKind::UnexpectedType => "unexpected_type",
+            ErrorKind::NullNotAllowed => "null_not_allowed",
+            ErrorKind::PrecisionLoss => "precision_loss",
+            ErrorKind::Other => "other",
+        }
+    }
+
+    /// The description after the code, the same as serde's for `invalid_value` and `invalid_type`.
+    const fn phrase(self) -> &'static str {
+        match self {
+            ErrorKind::InvalidFormat => "invalid value",
+            ErrorKind::OutOfRange => "out of range value",
+            ErrorKind::UnexpectedType => "invalid type",
+            ErrorKind::NullNotAllowed => "null not allowed",
+            ErrorKind::PrecisionLoss => "precision loss",
+            ErrorKind::Other => "",
+        }
+    }
+
+    /// The kind of an error message, found by its code. Formats may add to the message,
+    /// e.g. a position or a `field: ` path, so the code counts at the start or after a `: `.
+    pub fn from_message(message: &str) -> ErrorKind {
+        find(message).map_or(ErrorKind::Other, |(kind, _)| kind)
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// The first code in `message`, with the description after it.
+fn find(message: &str) -> Option<(ErrorKind, &str)> {
+    message.match_indices('[').find_map(|(at, _)| {
+        if at > 0 && !message[..at].ends_with(": ") {
+            return None;
+        }
+        let rest = &message[at + 1..];
+        let kind = KINDS.into_iter().find(|kind| rest.strip_prefix(kind.code()).is_some_and(|rest| rest.starts_with("] ")))?;
+        Some((kind, &rest[kind.code().len() + 2..]))
+    })
+}
+
+/// The message of an error of this crate.
+struct Message<'a> {
+    kind: ErrorKind,
+    input: Option<Unexpected<'a>>,
+    exp: &'a dyn Expected,
+}
+
+impl fmt::Display for Message<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", self.kind.code(), self.kind.phrase())?;
+        if let Some(input) = &self.input {
+            write!(f, ": {}", input)?;
+        }
+        write!(f, ", expected {}", self.exp)
+    }
+}
+
+pub(crate) fn invalid_format<E: Error>(input: Unexpected, exp: &dyn Expected) -> E {
+    E::custom(Message { kind: ErrorKind::InvalidFormat, input: Some(input), exp })
+}
+
+pub(crate) fn out_of_range<E: Error>(input: Unexpected, exp: &dyn Expected) -> E {
+    E::custom(Message { kind: ErrorKind::OutOfRange, input: Some(input), exp })
+}
+
+pub(crate) fn unexpected_type<E: Error>(input: Unexpected, exp: &dyn Expected) -> E {
+    E::custom(Message { kind: ErrorKind::UnexpectedType, input: Some(input), exp })
+}
+
+pub(crate) fn precision_loss<E: Error>(input: Unexpected, exp: &dyn Expected) -> E {
+    E::custom(Message { kind: ErrorKind::PrecisionLoss, input: Some(input), exp })
+}
+
+pub(crate) fn null_not_allowed<E: Error>(exp: &dyn Expected) -> E {
+    E::custom(Message { kind: ErrorKind::NullNotAllowed, input: None, exp })
+}
+
+#[cfg(feature = "alloc")]
+pub use self::flex_error::FlexError;
+
+#[cfg(feature = "alloc")]
+mod flex_error {
+    use super::{find, ErrorKind};
+    use alloc::borrow::ToOwned;
+    use alloc::string::{String, ToString};
+    use core::fmt;
+    use core::ops::Range;
+
+    /// An error with its kind, input and expectation.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct FlexError {
+        kind: ErrorKind,
+        message: String,
+        input: Range<usize>,
+        expected: Range<usize>,
+    }
+
+    impl FlexError {
+        /// Reads an error message, see [`ErrorKind::from_message`]. Messages without a code
+        /// are kept as [`ErrorKind::Other`].
+        pub fn from_message(message: &str) -> FlexError {
+            let message = message.to_owned();
+            let Some((kind, rest)) = find(&message) else {
+                return FlexError { kind: ErrorKind::Other, input: 0..0, expected: 0..0, message };
+            };
+            // `{phrase}: {input}, expected {exp}`, or `{phrase}, expected {exp}` without an input
+            let rest = rest.strip_prefix(kind.phrase()).unwrap_or(rest);
+            let start = message.len() - rest.len();
+            let end = rest.rfind(", expected ").map_or(message.len(), |at| start + at);
+            let input = match rest.starts_with(": ") {
+                true => start + 2..end,
+                false => start..start,
+            };
+            let expected = (end + ", expected ".len()).min(message.len())..message.len();
+            FlexError { kind, message, input, expected }
+        }
+
+        /// Finds the error of this crate in `err` or its sources, by downcasting to `FlexError`
+        /// or from the message.
+        #[cfg(feature = "std")]
+        pub fn from_error(err: &(dyn std::error::Error + 'static)) -> FlexError {
+            let mut source = Some(err);
+            while let Some(err) = source {
+                if let Some(err) = err.downcast_ref::<FlexError>() {
+                    return err.clone();
+                }
+                source = err.source();
+            }
+            FlexError::from_message(&err.to_string())
+        }
+
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+
+        /// The stable identifier of the kind, see [`ErrorKind::code`].
+        pub fn code(&self) -> &'static str {
+            self.kind.code()
+        }
+
+        /// The input as serde describes it, e.g. ``integer `-1` `` or `string "abc"`.
+        /// Empty for `null` and other errors.
+        pub fn input(&self) -> &str {
+            &self.message[self.input.clone()]
+        }
+
+        /// What the deserializer expected, including what the format added after it,
+        /// e.g. `an unsigned integer or a string at line 1 column 13` for `serde_json`.
+        pub fn expected(&self) -> &str {
+            &self.message[self.expected.clone()]
+        }
+    }
+
+    impl fmt::Display for FlexError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(&self.message)
+        }
+    }
+
+    impl serde::de::Error for FlexError {
+        fn custom<T: fmt::Display>(msg: T) -> Self {
+            FlexError::from_message(&msg.to_string())
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl std::error::Error for FlexError {}
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{as_bool, as_num, as_u64};
+    use crate::config::GlobalConfig;
+    use serde::de::value::{BoolDeserializer, F64Deserializer, StrDeserializer, U64Deserializer, UnitDeserializer};
+    use serde::de::IntoDeserializer;
+
+    #[test]
+    fn test_from_message() {
+        let json = |json: &str| serde_json::from_str::<serde_json::Value>(json).map(|v| as_u64(v).unwrap_err().to_string()).unwrap();
+        assert_eq!(ErrorKind::from_message(&json("-1")), ErrorKind::OutOfRange);
+        assert_eq!(ErrorKind::from_message(&json("null")), ErrorKind::NullNotAllowed);
+        assert_eq!(ErrorKind::from_message(&json("[]")), ErrorKind::UnexpectedType);
+        assert_eq!(ErrorKind::from_message(&json(r#""abc""#)), ErrorKind::InvalidFormat);
+        assert_eq!(ErrorKind::from_message("missing field `id` at line 1 column 2"), ErrorKind::Other);
+
+        // serde's own errors have the same wording, but no code
+        let plain = serde_json::from_str::<u64>(r#""x""#).unwrap_err().to_string();
+        assert!(plain.starts_with("invalid type: string \"x\", expected u64"));
+        assert_eq!(ErrorKind::from_message(&plain), ErrorKind::Other);
+        assert_eq!(FlexError::from_message(&plain).kind(), ErrorKind::Other);
+
+        // the first code counts, not one in the input
+        let err = FlexError::from_message(r#"[invalid_format] invalid value: string "[out_of_range] 1", expected a number"#);
+        assert_eq!((err.kind(), err.input(), err.expected()), (ErrorKind::InvalidFormat, r#"string "[out_of_range] 1""#, "a number"));
+        assert_eq!(ErrorKind::from_message(r#"invalid type: string "[out_of_range] 1", expected u64"#), ErrorKind::Other);
+
+        let err = FlexError::from_message("field: [null_not_allowed] null not allowed, expected a boolean at line 1 column 5");
+        assert_eq!((err.kind(), err.input(), err.expected()), (ErrorKind::NullNotAllowed, "", "a boolean at line 1 column 5"));
+
+        let err = FlexError::from_message("missing field `id`");
+        assert_eq!((err.kind(), err.input(), err.expected(), err.to_string().as_str()), (ErrorKind::Other, "", "", "missing field `id`"));
+    }
+
+    #[test]
+    fn test_message_format() {
+        // the message format is a stability contract, see the module documentation
+        let message = |kind, input| Message { kind, input, exp: &"a number" }.to_string();
+        assert_eq!(message(ErrorKind::InvalidFormat, Some(Unexpected::Str("x"))), r#"[invalid_format] invalid value: string "x", expected a number"#);
+        assert_eq!(message(ErrorKind::OutOfRange, Some(Unexpected::Signed(-1))), "[out_of_range] out of range value: integer `-1`, expected a number");
+        assert_eq!(message(ErrorKind::UnexpectedType, Some(Unexpected::Map)), "[unexpected_type] invalid type: map, expected a number");
+        assert_eq!(message(ErrorKind::NullNotAllowed, None), "[null_not_allowed] null not allowed, expected a number");
+        assert_eq!(message(ErrorKind::PrecisionLoss, Some(Unexpected::Float(2.5))), "[precision_loss] precision loss: floating point `2.5`, expected a number");
+
+        for kind in KINDS {
+            assert_eq!(ErrorKind::from_message(&message(kind, Some(Unexpected::Unit))), kind);
+        }
+    }
+
+    #[test]
+    fn test_as_error_type() {
+        let u8 = |v: u64| as_num::<u8, GlobalConfig, _>(U64Deserializer::<FlexError>::new(v));
+        let err = u8(300).unwrap_err();
+        assert_eq!((err.kind(), err.code(), err.input(), err.expected()), (ErrorKind::OutOfRange, "out_of_range", "integer `300`", "a value fitting in u8"));
+        assert_eq!(err.to_string(), "[out_of_range] out of range value: integer `300`, expected a value fitting in u8");
+
+        let str: StrDeserializer<FlexError> = "maybe".into_deserializer();
+        let err = as_bool(str).unwrap_err();
+        assert_eq!((err.kind(), err.input()), (ErrorKind::InvalidFormat, r#"string "maybe""#));
+
+        let err = as_u64(UnitDeserializer::<FlexError>::new()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::NullNotAllowed);
+
+        let err = crate::as_u64_config::<Reject, _>(F64Deserializer::<FlexError>::new(2.5)).unwrap_err();
+        assert_eq!((err.kind(), err.input()), (ErrorKind::PrecisionLoss, "floating point `2.5`"));
+
+        // serde's defaults for types a visitor doesn't accept get a code too
+        let err = as_u64(BoolDeserializer::<FlexError>::new(true)).unwrap_err();
+        assert_eq!((err.kind(), err.input()), (ErrorKind::UnexpectedType, "boolean `true`"));
+    }
+
+    struct Reject;
+
+    impl crate::config::Config for Reject {
+        fn config() -> crate::config::FlexConfig {
+            crate::config::FlexConfig::new().rounding(crate::config::Rounding::Reject)
+        }
+    }
+
+    #[test]
+    fn test_from_error() {
+        let err: Box<dyn std::error::Error> = Box::new(FlexError::from_message("[precision_loss] precision loss: floating point `2.5`, expected an integer"));
+        assert_eq!(FlexError::from_error(err.as_ref()).kind(), ErrorKind::PrecisionLoss);
+
+        let err = serde_json::from_str::<serde_json::Value>("[").unwrap_err();
+        assert_eq!(FlexError::from_error(&err).kind(), ErrorKind::Other);
+    }
+}
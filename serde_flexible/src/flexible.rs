@@ -0,0 +1,138 @@
+use crate::{as_bool, as_bool_opt, as_char, as_char_opt, as_f64, as_f64_opt, as_i64, as_i64_opt, as_u64, as_u64_opt};
+#[cfg(feature = "alloc")]
+use crate::{as_cow_str, as_cow_str_opt, as_string, as_string_opt};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String};
+use core::ops::{Deref, DerefMut};
+
+/// A value deserialized with the matching flexible deserializer, e.g. `Flexible<u64>` with `as_u64`
+/// and `Flexible<Option<bool>>` with `as_bool_opt`.
+///
+/// Unlike `#[serde(deserialize_with = "...")]` it also works inside containers, such as
+/// `Vec<Flexible<u64>>`, `HashMap<String, Flexible<bool>>` or tuples. It serializes as the inner value.
+///
+/// ```
+/// use serde_flexible::Flexible;
+///
+/// let ids: Vec<Flexible<u64>> = serde_json::from_str(r#"[1, "2", 3.0]"#).unwrap();
+/// assert_eq!(ids, [Flexible(1), Flexible(2), Flexible(3)]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Flexible<T>(pub T);
+
+impl<T> Flexible<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Flexible<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Flexible<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for Flexible<T> {
+    fn from(v: T) -> Self {
+        Flexible(v)
+    }
+}
+
+impl<T: Serialize> Serialize for Flexible<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+macro_rules! impl_flexible {
+    ($($ty:ty => $de:ident),+ $(,)?) => {
+        $(
+            impl<'de> Deserialize<'de> for Flexible<$ty> {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    $de(deserializer).map(Flexible)
+                }
+            }
+
+            impl<'de> From<Flexible<$ty>> for $ty {
+                fn from(v: Flexible<$ty>) -> Self {
+                    v.0
+                }
+            }
+        )+
+    };
+}
+
+impl_flexible! {
+    bool => as_bool,
+    char => as_char,
+    f64 => as_f64,
+    i64 => as_i64,
+    u64 => as_u64,
+    Option<bool> => as_bool_opt,
+    Option<char> => as_char_opt,
+    Option<f64> => as_f64_opt,
+    Option<i64> => as_i64_opt,
+    Option<u64> => as_u64_opt,
+}
+
+#[cfg(feature = "alloc")]
+impl_flexible! {
+    String => as_string,
+    Cow<'de, str> => as_cow_str,
+    Option<String> => as_string_opt,
+    Option<Cow<'de, str>> => as_cow_str_opt,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_containers() {
+        let v: Vec<Flexible<u64>> = serde_json::from_str(r#"[1, "2", 3.4, "1e3"]"#).unwrap();
+        assert_eq!(v.into_iter().map(u64::from).collect::<Vec<_>>(), vec![1, 2, 3, 1000]);
+
+        let m: HashMap<String, Flexible<bool>> = serde_json::from_str(r#"{"a": "yes", "b": 0, "c": true}"#).unwrap();
+        assert!(*m["a"] && !*m["b"] && *m["c"]);
+
+        let t: (Flexible<String>, Flexible<f64>, Flexible<i64>, Flexible<char>) = serde_json::from_str(r#"[12, "2.5", "-3", 65]"#).unwrap();
+        assert_eq!(t, (Flexible("12".to_string()), Flexible(2.5), Flexible(-3), Flexible('A')));
+
+        let v: Vec<Flexible<Option<u64>>> = serde_json::from_str(r#"[null, "none", "7"]"#).unwrap();
+        assert_eq!(v, [Flexible(None), Flexible(None), Flexible(Some(7))]);
+
+        let v: Vec<Option<Flexible<i64>>> = serde_json::from_str(r#"[null, "-7"]"#).unwrap();
+        assert_eq!(v, [None, Some(Flexible(-7))]);
+
+        let v: Vec<Flexible<Cow<str>>> = serde_json::from_str(r#"["a", 1]"#).unwrap();
+        assert!(matches!(v[0].0, Cow::Borrowed("a")));
+        assert_eq!(v[1].as_ref(), "1");
+    }
+
+    #[test]
+    fn test_base_error() {
+        assert!(serde_json::from_str::<Vec<Flexible<u64>>>(r#"[1, "x"]"#).is_err());
+        assert!(serde_json::from_str::<Vec<Flexible<bool>>>(r#"[null]"#).is_err());
+    }
+
+    #[test]
+    fn test_serialize_transparent() {
+        assert_eq!(serde_json::to_string(&Flexible(5u64)).unwrap(), "5");
+        assert_eq!(serde_json::to_string(&vec![Flexible(Some(true)), Flexible(None)]).unwrap(), "[true,null]");
+
+        let mut v = Flexible(1u64);
+        *v += 1;
+        assert_eq!(v.into_inner(), 2);
+    }
+}
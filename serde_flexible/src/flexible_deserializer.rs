@@ -0,0 +1,403 @@
+use crate::sentinels::{is_sentinel, null_sentinels};
+use crate::{as_bool, as_char, as_cow_str, as_f64, as_i64, as_u64};
+use serde::de::value::{
+    BoolDeserializer, BorrowedBytesDeserializer, BorrowedStrDeserializer, BytesDeserializer, CharDeserializer,
+    EnumAccessDeserializer, F64Deserializer, I64Deserializer, MapAccessDeserializer, SeqAccessDeserializer,
+    StrDeserializer, StringDeserializer, U64Deserializer,
+};
+use serde::de::{DeserializeSeed, Deserializer, EnumAccess, Error, MapAccess, SeqAccess, VariantAccess, Visitor};
+use alloc::borrow::Cow;
+use core::fmt;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// A [`Deserializer`] that applies the flexible rules to a whole document.
+///
+/// Whenever the target type asks for an integer, a float, a `bool`, a `char` or a string,
+/// the value is deserialized like `as_u64`, `as_i64`, `as_f64`, `as_bool`, `as_char` or
+/// `as_cow_str` would. `Option`s treat `null` and the global null sentinels as `None`.
+/// Nested structs, sequences, maps and enums are wrapped as well, so the target type needs
+/// no annotations:
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_flexible::FlexibleDeserializer;
+///
+/// #[derive(Deserialize)]
+/// struct Item {
+///     id: u64,
+///     price: Option<f64>,
+///     tags: Vec<String>,
+///     active: bool,
+/// }
+///
+/// let mut json = serde_json::Deserializer::from_str(r#"{"id": "7", "price": "none", "tags": [1, "a"], "active": "yes"}"#);
+/// let item = Item::deserialize(FlexibleDeserializer::new(&mut json)).unwrap();
+/// assert_eq!((item.id, item.price, item.tags, item.active), (7, None, vec!["1".to_string(), "a".to_string()], true));
+/// ```
+///
+/// Values that serde buffers internally (`#[serde(flatten)]` and untagged or internally tagged
+/// enums) are deserialized without coercion.
+pub struct FlexibleDeserializer<D>(D);
+
+impl<D> FlexibleDeserializer<D> {
+    pub fn new(deserializer: D) -> Self {
+        FlexibleDeserializer(deserializer)
+    }
+
+    pub fn into_inner(self) -> D {
+        self.0
+    }
+}
+
+macro_rules! coerce {
+    ($($method:ident => $de:ident, $visit:ident;)+) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit($de(self.0)?)
+            }
+        )+
+    };
+}
+
+macro_rules! forward {
+    ($($method:ident($($arg:ident: $ty:ty),*);)+) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error> {
+                self.0.$method($($arg,)* Wrap(visitor))
+            }
+        )+
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for FlexibleDeserializer<D> {
+    type Error = D::Error;
+
+    coerce! {
+        deserialize_bool => as_bool, visit_bool;
+        deserialize_i8 => as_i64, visit_i64;
+        deserialize_i16 => as_i64, visit_i64;
+        deserialize_i32 => as_i64, visit_i64;
+        deserialize_i64 => as_i64, visit_i64;
+        deserialize_u8 => as_u64, visit_u64;
+        deserialize_u16 => as_u64, visit_u64;
+        deserialize_u32 => as_u64, visit_u64;
+        deserialize_u64 => as_u64, visit_u64;
+        deserialize_f32 => as_f64, visit_f64;
+        deserialize_f64 => as_f64, visit_f64;
+        deserialize_char => as_char, visit_char;
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match as_cow_str(self.0)? {
+            Cow::Borrowed(v) => visitor.visit_borrowed_str(v),
+            Cow::Owned(v) => visitor.visit_string(v),
+        }
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.0.deserialize_any(OptionVisitor(visitor))
+    }
+
+    forward! {
+        deserialize_any();
+        deserialize_i128();
+        deserialize_u128();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_unit();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+/// Passes values through to `V`, wrapping every nested deserializer.
+struct Wrap<V>(V);
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for Wrap<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> { self.0.visit_bool(v) }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { self.0.visit_i64(v) }
+    fn visit_i128<E: Error>(self, v: i128) -> Result<Self::Value, E> { self.0.visit_i128(v) }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { self.0.visit_u64(v) }
+    fn visit_u128<E: Error>(self, v: u128) -> Result<Self::Value, E> { self.0.visit_u128(v) }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { self.0.visit_f64(v) }
+    fn visit_char<E: Error>(self, v: char) -> Result<Self::Value, E> { self.0.visit_char(v) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { self.0.visit_str(v) }
+    fn visit_borrowed_str<E: Error>(self, v: &'de str) -> Result<Self::Value, E> { self.0.visit_borrowed_str(v) }
+    fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> { self.0.visit_string(v) }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> { self.0.visit_bytes(v) }
+    fn visit_borrowed_bytes<E: Error>(self, v: &'de [u8]) -> Result<Self::Value, E> { self.0.visit_borrowed_bytes(v) }
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> { self.0.visit_byte_buf(v) }
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> { self.0.visit_none() }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { self.0.visit_unit() }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.visit_some(FlexibleDeserializer(deserializer))
+    }
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.visit_newtype_struct(FlexibleDeserializer(deserializer))
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_seq(Wrap(seq))
+    }
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_map(Wrap(map))
+    }
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.0.visit_enum(Wrap(data))
+    }
+}
+
+impl<'de, T: DeserializeSeed<'de>> DeserializeSeed<'de> for Wrap<T> {
+    type Value = T::Value;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.0.deserialize(FlexibleDeserializer(deserializer))
+    }
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for Wrap<A> {
+    type Error = A::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error> {
+        self.0.next_element_seed(Wrap(seed))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A: MapAccess<'de>> MapAccess<'de> for Wrap<A> {
+    type Error = A::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error> {
+        self.0.next_key_seed(Wrap(seed))
+    }
+
+    fn next_value_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<T::Value, Self::Error> {
+        self.0.next_value_seed(Wrap(seed))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.0.size_hint()
+    }
+}
+
+impl<'de, A: EnumAccess<'de>> EnumAccess<'de> for Wrap<A> {
+    type Error = A::Error;
+    type Variant = Wrap<A::Variant>;
+
+    fn variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<(T::Value, Self::Variant), Self::Error> {
+        self.0.variant_seed(Wrap(seed)).map(|(v, variant)| (v, Wrap(variant)))
+    }
+}
+
+impl<'de, A: VariantAccess<'de>> VariantAccess<'de> for Wrap<A> {
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        self.0.unit_variant()
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Self::Error> {
+        self.0.newtype_variant_seed(Wrap(seed))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error> {
+        self.0.tuple_variant(len, Wrap(visitor))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value, Self::Error> {
+        self.0.struct_variant(fields, Wrap(visitor))
+    }
+}
+
+/// Maps `null` and null sentinels to `visit_none`, and replays anything else into `visit_some`.
+struct OptionVisitor<V>(V);
+
+impl<V> OptionVisitor<V> {
+    fn some<'de, D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.visit_some(FlexibleDeserializer(deserializer))
+    }
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for OptionVisitor<V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> { self.some(BoolDeserializer::new(v)) }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { self.some(I64Deserializer::new(v)) }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { self.some(U64Deserializer::new(v)) }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { self.some(F64Deserializer::new(v)) }
+    fn visit_char<E: Error>(self, v: char) -> Result<Self::Value, E> { self.some(CharDeserializer::new(v)) }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+        if is_sentinel(null_sentinels(), v) {
+            return self.0.visit_none();
+        }
+        self.some(StrDeserializer::new(v))
+    }
+    fn visit_borrowed_str<E: Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        if is_sentinel(null_sentinels(), v) {
+            return self.0.visit_none();
+        }
+        self.some(BorrowedStrDeserializer::new(v))
+    }
+    fn visit_string<E: Error>(self, v: String) -> Result<Self::Value, E> {
+        if is_sentinel(null_sentinels(), &v) {
+            return self.0.visit_none();
+        }
+        self.some(StringDeserializer::new(v))
+    }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        match core::str::from_utf8(v) {
+            Ok(s) if is_sentinel(null_sentinels(), s) => self.0.visit_none(),
+            _ => self.some(BytesDeserializer::new(v)),
+        }
+    }
+    fn visit_borrowed_bytes<E: Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        match core::str::from_utf8(v) {
+            Ok(s) if is_sentinel(null_sentinels(), s) => self.0.visit_none(),
+            _ => self.some(BorrowedBytesDeserializer::new(v)),
+        }
+    }
+    fn visit_byte_buf<E: Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        self.visit_bytes(&v)
+    }
+
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> { self.0.visit_none() }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { self.0.visit_none() }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.some(deserializer)
+    }
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.some(deserializer)
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.some(SeqAccessDeserializer::new(seq))
+    }
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.some(MapAccessDeserializer::new(map))
+    }
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.some(EnumAccessDeserializer::new(data))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashMap;
+
+    fn from_str<'de, T: Deserialize<'de>>(s: &'de str) -> serde_json::Result<T> {
+        let mut de = serde_json::Deserializer::from_str(s);
+        let v = T::deserialize(FlexibleDeserializer::new(&mut de))?;
+        de.end()?;
+        Ok(v)
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Inner {
+        count: u32,
+        ratio: f32,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Kind {
+        Plain,
+        Sized(u8),
+        Point { x: i16, y: i16 },
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Test<'a> {
+        id: u64,
+        delta: i64,
+        price: f64,
+        active: bool,
+        letter: char,
+        name: String,
+        #[serde(borrow)]
+        code: Cow<'a, str>,
+        missing: Option<u64>,
+        sentinel: Option<bool>,
+        present: Option<i32>,
+        inner: Inner,
+        nested: Option<Inner>,
+        list: Vec<u16>,
+        map: HashMap<String, bool>,
+        kinds: Vec<Kind>,
+        tuple: (u8, String),
+    }
+
+    #[test]
+    fn test_base_good_parse() {
+        let t: Test = from_str(r#"{
+            "id": "7", "delta": "-3.0", "price": "1.5", "active": "yes", "letter": 65,
+            "name": 12, "code": "abc", "missing": null, "sentinel": "none", "present": "5",
+            "inner": {"count": "3", "ratio": "0.5"}, "nested": {"count": 1.0, "ratio": 2},
+            "list": ["1", 2, 3.0], "map": {"a": "on", "b": 0},
+            "kinds": ["Plain", {"Sized": "8"}, {"Point": {"x": "-1", "y": 2}}],
+            "tuple": ["4", true]
+        }"#).unwrap();
+
+        assert_eq!((t.id, t.delta, t.price, t.active, t.letter), (7, -3, 1.5, true, 'A'));
+        assert_eq!(t.name, "12");
+        assert!(matches!(t.code, Cow::Borrowed("abc")));
+        assert_eq!((t.missing, t.sentinel, t.present), (None, None, Some(5)));
+        assert_eq!(t.inner, Inner { count: 3, ratio: 0.5 });
+        assert_eq!(t.nested, Some(Inner { count: 1, ratio: 2.0 }));
+        assert_eq!(t.list, [1, 2, 3]);
+        assert_eq!(t.map, HashMap::from([("a".to_string(), true), ("b".to_string(), false)]));
+        assert_eq!(t.kinds, [Kind::Plain, Kind::Sized(8), Kind::Point { x: -1, y: 2 }]);
+        assert_eq!(t.tuple, (4, "true".to_string()));
+    }
+
+    #[test]
+    fn test_nested_options() {
+        assert_eq!(from_str::<Vec<Option<u64>>>(r#"["1", "unknown", null, 2.0]"#).unwrap(), [Some(1), None, None, Some(2)]);
+        assert_eq!(from_str::<Option<Vec<u8>>>(r#"["1"]"#).unwrap(), Some(vec![1]));
+        assert_eq!(from_str::<Option<Option<u8>>>(r#""NULL""#).unwrap(), None);
+    }
+
+    #[test]
+    fn test_base_error() {
+        assert!(from_str::<u64>(r#""x""#).is_err());
+        assert!(from_str::<u8>(r#""300""#).is_err());
+        assert!(from_str::<bool>(r#"null"#).is_err());
+        assert!(from_str::<Option<u64>>(r#""x""#).is_err());
+        assert!(from_str::<Vec<u64>>(r#"["1", "-1"]"#).is_err());
+    }
+}
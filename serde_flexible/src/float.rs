@@ -0,0 +1,51 @@
+//! `f64` rounding, which `core` does not provide.
+
+/// From `2^52` on, every `f64` is an integer.
+const INTEGRAL: f64 = 4_503_599_627_370_496.0;
+
+pub(crate) fn trunc(v: f64) -> f64 {
+    if v > -INTEGRAL && v < INTEGRAL {
+        (v as i64) as f64
+    } else {
+        v // already integral, infinite or NaN
+    }
+}
+
+pub(crate) fn floor(v: f64) -> f64 {
+    let t = trunc(v);
+    if t > v { t - 1.0 } else { t }
+}
+
+pub(crate) fn ceil(v: f64) -> f64 {
+    let t = trunc(v);
+    if t < v { t + 1.0 } else { t }
+}
+
+/// Rounds halfway cases away from zero, like `f64::round`.
+pub(crate) fn round(v: f64) -> f64 {
+    let t = trunc(v);
+    match v - t {
+        d if d >= 0.5 => t + 1.0,
+        d if d <= -0.5 => t - 1.0,
+        _ => t,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_as_std() {
+        let values = [0.0, -0.0, 0.4, 0.5, 0.6, -0.5, -1.5, 2.5, -2.7, 1e15 + 0.5, -1e15 - 0.5, 4503599627370497.0, 1e300, -1e300, f64::MAX, f64::MIN];
+        for v in values {
+            assert_eq!(trunc(v), v.trunc(), "trunc {v}");
+            assert_eq!(floor(v), v.floor(), "floor {v}");
+            assert_eq!(ceil(v), v.ceil(), "ceil {v}");
+            assert_eq!(round(v), v.round(), "round {v}");
+        }
+        assert!(round(f64::NAN).is_nan());
+        assert_eq!(floor(f64::NEG_INFINITY), f64::NEG_INFINITY);
+    }
+}
@@ -0,0 +1,114 @@
+//! Reporting for the lenient deserializers (`as_f64_lenient`, ...).
+//!
+//! Lenient deserializers return `None` instead of failing on input they can't parse. Every
+//! dropped value is passed to the handler set with [`set_discard_handler`] and recorded by
+//! [`collect_discarded`], so callers can log what was lost:
+//!
+//! ```
+//! use serde::Deserialize;
+//! use serde_flexible::as_f64_lenient;
+//! use serde_flexible::lenient::{collect_discarded, Raw};
+//!
+//! #[derive(Deserialize)]
+//! struct Record {
+//!     #[serde(deserialize_with = "as_f64_lenient")]
+//!     price: Option<f64>,
+//! }
+//!
+//! let (record, discarded) = collect_discarded(|| serde_json::from_str::<Record>(r#"{"price": "abc"}"#));
+//! assert_eq!(record.unwrap().price, None);
+//! assert_eq!(discarded[0].raw, Raw::Str("abc".to_string()));
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::RwLock;
+
+/// The input a lenient deserializer dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Raw {
+    Bool(bool),
+    Signed(i64),
+    Unsigned(u64),
+    Float(f64),
+    Char(char),
+    Str(String),
+    Bytes(Vec<u8>),
+    Seq,
+    Map,
+}
+
+/// A value that was replaced by `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Discarded {
+    /// The input as it was received.
+    pub raw: Raw,
+    /// What the deserializer expected, e.g. `"null, a float, an integer, or a string"`.
+    pub expected: &'static str,
+    /// The error the strict deserializer would have returned.
+    pub error: String,
+}
+
+impl fmt::Display for Raw {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Raw::Bool(v) => write!(f, "boolean `{}`", v),
+            Raw::Signed(v) => write!(f, "integer `{}`", v),
+            Raw::Unsigned(v) => write!(f, "integer `{}`", v),
+            Raw::Float(v) => write!(f, "floating point `{}`", v),
+            Raw::Char(v) => write!(f, "character `{}`", v),
+            Raw::Str(v) => write!(f, "string {:?}", v),
+            Raw::Bytes(v) => write!(f, "byte array {:?}", v),
+            Raw::Seq => f.write_str("sequence"),
+            Raw::Map => f.write_str("map"),
+        }
+    }
+}
+
+impl fmt::Display for Discarded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "discarded {}, expected {}", self.raw, self.expected)
+    }
+}
+
+static DISCARD_HANDLER: RwLock<Option<fn(&Discarded)>> = RwLock::new(None);
+
+thread_local! {
+    static COLLECTORS: RefCell<Vec<Vec<Discarded>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Sets a process wide handler that is called for every discarded value, e.g. to log it.
+/// `None` removes the handler.
+pub fn set_discard_handler(handler: Option<fn(&Discarded)>) {
+    *DISCARD_HANDLER.write().unwrap_or_else(|err| err.into_inner()) = handler;
+}
+
+/// Runs `f` and returns its result together with all values discarded on this thread meanwhile.
+/// Calls can be nested, each one only sees the values discarded within it.
+pub fn collect_discarded<R>(f: impl FnOnce() -> R) -> (R, Vec<Discarded>) {
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            COLLECTORS.with(|c| c.borrow_mut().pop());
+        }
+    }
+
+    COLLECTORS.with(|c| c.borrow_mut().push(Vec::new()));
+    let guard = Guard;
+    let result = f();
+    let discarded = COLLECTORS.with(|c| c.borrow_mut().last_mut().map(std::mem::take).unwrap_or_default());
+    drop(guard);
+    (result, discarded)
+}
+
+pub(crate) fn discard(discarded: Discarded) {
+    if let Some(handler) = *DISCARD_HANDLER.read().unwrap_or_else(|err| err.into_inner()) {
+        handler(&discarded);
+    }
+    COLLECTORS.with(|c| {
+        if let Some(collector) = c.borrow_mut().last_mut() {
+            collector.push(discarded);
+        }
+    });
+}
@@ -1,13 +1,72 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+pub mod config;
 mod deserialize;
+pub mod error;
+mod flexible;
+#[cfg(feature = "alloc")]
+mod flexible_deserializer;
+mod float;
+#[cfg(feature = "std")]
+pub mod lenient;
+#[cfg(feature = "alloc")]
+pub mod normalize;
+pub mod patch;
+pub mod sentinels;
+#[cfg(feature = "serde_with")]
+pub mod serde_as;
+mod shaped;
+mod with;
+
+#[cfg(feature = "std")]
+pub use config::with_config;
+#[cfg(feature = "alloc")]
+pub use error::FlexError;
+pub use flexible::Flexible;
+#[cfg(feature = "alloc")]
+pub use flexible_deserializer::FlexibleDeserializer;
+pub use shaped::{Shape, Shaped};
+pub use with::{bool, char, f64, i64, u64};
+#[cfg(feature = "alloc")]
+pub use with::{cow_str, string};
+#[cfg(feature = "serde_with")]
+pub use serde_as::*;
+
+#[cfg(feature = "derive")]
+pub use serde_flexible_derive::flexible;
+
+pub use deserialize::as_bool::{as_bool, as_bool_config};
+pub use deserialize::as_char::{as_char, as_char_config};
+pub use deserialize::as_f64::{as_f64, as_f64_config};
+pub use deserialize::as_flexible::{as_flexible, as_flexible_config, as_flexible_opt, as_flexible_opt_config, FlexibleParse};
+pub use deserialize::as_i64::{as_i64, as_i64_config};
+pub use deserialize::as_num::{as_num, as_num_opt, Num};
+pub use deserialize::as_u64::{as_u64, as_u64_config};
+
+pub use deserialize::as_bool_opt::{as_bool_opt, as_bool_opt_config, as_bool_opt_with};
+pub use deserialize::as_char_opt::{as_char_opt, as_char_opt_config, as_char_opt_with};
+pub use deserialize::as_f64_opt::{as_f64_opt, as_f64_opt_config, as_f64_opt_with};
+pub use deserialize::as_i64_opt::{as_i64_opt, as_i64_opt_config, as_i64_opt_with};
+pub use deserialize::as_u64_opt::{as_u64_opt, as_u64_opt_config, as_u64_opt_with};
+
+pub use deserialize::as_lenient::{as_bool_lenient, as_char_lenient, as_f64_lenient, as_i64_lenient, as_u64_lenient};
+pub use deserialize::as_or_default::{as_bool_or_default, as_f64_or_default, as_i64_or_default, as_u64_or_default};
+pub use deserialize::as_or_default::{as_bool_or, as_i64_or, as_u64_or};
+
+pub use deserialize::as_patch::{as_bool_patch, as_char_patch, as_f64_patch, as_i64_patch, as_u64_patch};
 
-pub use deserialize::as_bool::as_bool;
-pub use deserialize::as_f64::as_f64;
-pub use deserialize::as_i64::as_i64;
-pub use deserialize::as_string::as_string;
-pub use deserialize::as_u64::as_u64;
-
-pub use deserialize::as_bool_opt::as_bool_opt;
-pub use deserialize::as_f64_opt::as_f64_opt;
-pub use deserialize::as_i64_opt::as_i64_opt;
-pub use deserialize::as_string_opt::as_string_opt;
-pub use deserialize::as_u64_opt::as_u64_opt;
+#[cfg(feature = "alloc")]
+pub use deserialize::as_cow_str::as_cow_str;
+#[cfg(feature = "alloc")]
+pub use deserialize::as_cow_str_opt::{as_cow_str_opt, as_cow_str_opt_with};
+#[cfg(feature = "alloc")]
+pub use deserialize::as_patch::as_string_patch;
+#[cfg(feature = "alloc")]
+pub use deserialize::as_string::{as_string, as_string_lossy};
+#[cfg(feature = "alloc")]
+pub use deserialize::as_string_normalized::{as_string_normalized, as_string_normalized_opt};
+#[cfg(feature = "alloc")]
+pub use deserialize::as_string_opt::{as_string_opt, as_string_opt_non_blank, as_string_opt_non_empty, as_string_opt_sentinel, as_string_opt_with};
@@ -0,0 +1,122 @@
+//! String normalizations for [`as_string_normalized`](crate::as_string_normalized) and
+//! [`as_string_normalized_opt`](crate::as_string_normalized_opt).
+//!
+//! Normalizations can be combined with tuples and are applied from left to right,
+//! e.g. `as_string_normalized::<(Trim, Lowercase, Nfc), _>`.
+
+use alloc::borrow::ToOwned;
+use alloc::string::String;
+
+/// A normalization applied to a deserialized string.
+pub trait Normalize {
+    fn normalize(v: String) -> String;
+}
+
+/// Removes leading and trailing whitespace.
+pub struct Trim;
+
+/// Converts the string to lowercase with [`str::to_lowercase`].
+///
+/// This is lowercasing, not Unicode case folding: `"Straße"` and `"STRASSE"` stay different
+/// (`"straße"` and `"strasse"`), and a final `Σ` becomes `ς`, not `σ`.
+pub struct Lowercase;
+
+/// Converts the string to uppercase with [`str::to_uppercase`].
+pub struct Uppercase;
+
+/// Unicode Normalization Form C (canonical composition).
+#[cfg(feature = "unicode")]
+pub struct Nfc;
+
+/// Unicode Normalization Form D (canonical decomposition).
+#[cfg(feature = "unicode")]
+pub struct Nfd;
+
+/// Unicode Normalization Form KC (compatibility composition).
+#[cfg(feature = "unicode")]
+pub struct Nfkc;
+
+/// Unicode Normalization Form KD (compatibility decomposition).
+#[cfg(feature = "unicode")]
+pub struct Nfkd;
+
+impl Normalize for () {
+    fn normalize(v: String) -> String { v }
+}
+
+impl Normalize for Trim {
+    fn normalize(v: String) -> String {
+        match v.trim() {
+            trimmed if trimmed.len() == v.len() => v,
+            trimmed => trimmed.to_owned(),
+        }
+    }
+}
+
+impl Normalize for Lowercase {
+    fn normalize(v: String) -> String { v.to_lowercase() }
+}
+
+impl Normalize for Uppercase {
+    fn normalize(v: String) -> String { v.to_uppercase() }
+}
+
+#[cfg(feature = "unicode")]
+mod unicode {
+    use super::{Nfc, Nfd, Nfkc, Nfkd, Normalize};
+    use alloc::string::String;
+    use unicode_normalization::{is_nfc_quick, is_nfd_quick, is_nfkc_quick, is_nfkd_quick, IsNormalized, UnicodeNormalization};
+
+    impl Normalize for Nfc {
+        fn normalize(v: String) -> String {
+            match is_nfc_quick(v.chars()) {
+                IsNormalized::Yes => v,
+                _ => v.nfc().collect(),
+            }
+        }
+    }
+
+    impl Normalize for Nfd {
+        fn normalize(v: String) -> String {
+            match is_nfd_quick(v.chars()) {
+                IsNormalized::Yes => v,
+                _ => v.nfd().collect(),
+            }
+        }
+    }
+
+    impl Normalize for Nfkc {
+        fn normalize(v: String) -> String {
+            match is_nfkc_quick(v.chars()) {
+                IsNormalized::Yes => v,
+                _ => v.nfkc().collect(),
+            }
+        }
+    }
+
+    impl Normalize for Nfkd {
+        fn normalize(v: String) -> String {
+            match is_nfkd_quick(v.chars()) {
+                IsNormalized::Yes => v,
+                _ => v.nfkd().collect(),
+            }
+        }
+    }
+}
+
+macro_rules! impl_normalize_tuple {
+    ($($n:ident),+) => {
+        impl<$($n: Normalize),+> Normalize for ($($n,)+) {
+            fn normalize(v: String) -> String {
+                $(let v = $n::normalize(v);)+
+                v
+            }
+        }
+    };
+}
+
+impl_normalize_tuple!(A);
+impl_normalize_tuple!(A, B);
+impl_normalize_tuple!(A, B, C);
+impl_normalize_tuple!(A, B, C, D);
+impl_normalize_tuple!(A, B, C, D, F);
@@ -0,0 +1,104 @@
+//! Three-state values for JSON Merge Patch style updates.
+
+use serde::ser::Error;
+use serde::{Serialize, Serializer};
+
+/// A field of a patch document: absent, explicitly `null`, or set to a value.
+///
+/// Use it with the `_patch` deserializers (`as_u64_patch`, ...) together with `#[serde(default)]`,
+/// so that an absent field becomes [`Patch::Missing`]:
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_flexible::as_u64_patch;
+/// use serde_flexible::patch::Patch;
+///
+/// #[derive(Deserialize)]
+/// struct UserPatch {
+///     #[serde(default, deserialize_with = "as_u64_patch")]
+///     age: Patch<u64>,
+/// }
+///
+/// let mut age = Some(30);
+/// serde_json::from_str::<UserPatch>(r#"{}"#).unwrap().age.apply_to(&mut age);
+/// assert_eq!(age, Some(30));
+/// serde_json::from_str::<UserPatch>(r#"{"age": "31"}"#).unwrap().age.apply_to(&mut age);
+/// assert_eq!(age, Some(31));
+/// serde_json::from_str::<UserPatch>(r#"{"age": null}"#).unwrap().age.apply_to(&mut age);
+/// assert_eq!(age, None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Patch<T> {
+    /// The field was absent: leave the target unchanged.
+    #[default]
+    Missing,
+    /// The field was `null` or a null sentinel: clear the target.
+    Null,
+    /// The field had a value: set the target.
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    pub fn is_missing(&self) -> bool { matches!(self, Patch::Missing) }
+
+    pub fn is_null(&self) -> bool { matches!(self, Patch::Null) }
+
+    pub fn is_value(&self) -> bool { matches!(self, Patch::Value(_)) }
+
+    pub fn as_ref(&self) -> Patch<&T> {
+        match self {
+            Patch::Missing => Patch::Missing,
+            Patch::Null => Patch::Null,
+            Patch::Value(v) => Patch::Value(v),
+        }
+    }
+
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Patch<U> {
+        match self {
+            Patch::Missing => Patch::Missing,
+            Patch::Null => Patch::Null,
+            Patch::Value(v) => Patch::Value(f(v)),
+        }
+    }
+
+    /// Applies the patch: `Missing` keeps `target`, `Null` clears it and `Value` replaces it.
+    pub fn apply_to(self, target: &mut Option<T>) {
+        match self {
+            Patch::Missing => {}
+            Patch::Null => *target = None,
+            Patch::Value(v) => *target = Some(v),
+        }
+    }
+}
+
+impl<T> From<Option<T>> for Patch<T> {
+    fn from(v: Option<T>) -> Self {
+        match v {
+            None => Patch::Null,
+            Some(v) => Patch::Value(v),
+        }
+    }
+}
+
+impl<T> From<Patch<T>> for Option<Option<T>> {
+    fn from(v: Patch<T>) -> Self {
+        match v {
+            Patch::Missing => None,
+            Patch::Null => Some(None),
+            Patch::Value(v) => Some(Some(v)),
+        }
+    }
+}
+
+/// Serializes `Value` as the value and `Null` as `null`. `Missing` is an error, because writing it
+/// as `null` would turn "leave unchanged" into "clear": pair `Patch` fields with
+/// `#[serde(skip_serializing_if = "Patch::is_missing")]` to omit absent fields instead.
+impl<T: Serialize> Serialize for Patch<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Patch::Missing => Err(S::Error::custom("`Patch::Missing` can't be serialized, skip it with `skip_serializing_if = \"Patch::is_missing\"`")),
+            Patch::Null => serializer.serialize_none(),
+            Patch::Value(v) => serializer.serialize_some(v),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Null sentinels: strings that the `_opt` deserializers treat like `null`.
+//!
+//! The default `_opt` deserializers (`as_u64_opt`, `as_bool_opt`, ...) use the global set,
+//! which starts as [`DEFAULT_NULL_SENTINELS`] and can be replaced with [`set_null_sentinels`].
+//! The `_with` variants (`as_u64_opt_with::<S, _>`, ...) take the set from a [`NullSentinels`]
+//! type instead, so it can be chosen per field:
+//!
+//! ```
+//! use serde::Deserialize;
+//! use serde_flexible::as_u64_opt_with;
+//! use serde_flexible::sentinels::NullSentinels;
+//!
+//! struct Feed;
+//!
+//! impl NullSentinels for Feed {
+//!     fn sentinels() -> &'static [&'static str] { &["N/A", "-", "nil", "\\N", "#N/A", ""] }
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct Row {
+//!     #[serde(deserialize_with = "as_u64_opt_with::<Feed, _>")]
+//!     count: Option<u64>,
+//! }
+//!
+//! let row: Row = serde_json::from_str(r#"{"count": "n/a"}"#).unwrap();
+//! assert_eq!(row.count, None);
+//! ```
+//!
+//! Sentinels are matched ASCII case insensitively. Without the `std` feature there is no global
+//! set, [`null_sentinels`] is always [`DEFAULT_NULL_SENTINELS`].
+
+use crate::config::config;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::sync::RwLock;
+
+/// The sentinels used when nothing else is configured.
+pub const DEFAULT_NULL_SENTINELS: &[&str] = &["null", "none", "unknown"];
+
+#[cfg(feature = "std")]
+static GLOBAL_NULL_SENTINELS: RwLock<&'static [&'static str]> = RwLock::new(DEFAULT_NULL_SENTINELS);
+
+/// A set of strings deserialized as `None`.
+pub trait NullSentinels {
+    fn sentinels() -> &'static [&'static str];
+}
+
+/// The global set, see [`set_null_sentinels`], unless the global
+/// [configuration](crate::config) sets its own.
+pub struct GlobalSentinels;
+
+/// Always [`DEFAULT_NULL_SENTINELS`], regardless of the global set.
+pub struct DefaultSentinels;
+
+impl NullSentinels for GlobalSentinels {
+    fn sentinels() -> &'static [&'static str] { config().sentinels() }
+}
+
+impl NullSentinels for DefaultSentinels {
+    fn sentinels() -> &'static [&'static str] { DEFAULT_NULL_SENTINELS }
+}
+
+/// Replaces the global sentinel set used by the default `_opt` deserializers.
+#[cfg(feature = "std")]
+pub fn set_null_sentinels(sentinels: &'static [&'static str]) {
+    *GLOBAL_NULL_SENTINELS.write().unwrap_or_else(|err| err.into_inner()) = sentinels;
+}
+
+/// Returns the global sentinel set.
+#[cfg(feature = "std")]
+pub fn null_sentinels() -> &'static [&'static str] {
+    *GLOBAL_NULL_SENTINELS.read().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Returns the global sentinel set.
+#[cfg(not(feature = "std"))]
+pub fn null_sentinels() -> &'static [&'static str] {
+    DEFAULT_NULL_SENTINELS
+}
+
+pub(crate) fn is_sentinel(sentinels: &[&str], v: &str) -> bool {
+    sentinels.iter().any(|s| s.eq_ignore_ascii_case(v))
+}
+
+pub(crate) fn write_expecting(formatter: &mut fmt::Formatter<'_>, exp: &str, sentinels: &[&str]) -> fmt::Result {
+    formatter.write_str(exp)?;
+    write_sentinels(formatter, sentinels)
+}
+
+pub(crate) fn write_sentinels(formatter: &mut fmt::Formatter<'_>, sentinels: &[&str]) -> fmt::Result {
+    if let Some((first, rest)) = sentinels.split_first() {
+        write!(formatter, " (null sentinels: {:?}", first)?;
+        for s in rest {
+            write!(formatter, ", {:?}", s)?;
+        }
+        formatter.write_str(")")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,230 @@
+//! Marker types for [`serde_with`](https://docs.rs/serde_with), one per deserializer.
+//!
+//! Each marker implements `DeserializeAs` with the function of the same name (`AsU64` with
+//! `as_u64`, `AsBoolOpt` with `as_bool_opt`, ...) and `SerializeAs` by serializing the value as is,
+//! so they compose with `serde_with`'s container support:
+//!
+//! ```
+//! use serde::Deserialize;
+//! use serde_flexible::{AsBool, AsU64};
+//! use serde_with::serde_as;
+//!
+//! #[serde_as]
+//! #[derive(Deserialize)]
+//! struct Data {
+//!     #[serde_as(as = "Vec<AsU64>")]
+//!     ids: Vec<u64>,
+//!     #[serde_as(as = "Option<AsBool>")]
+//!     flag: Option<bool>,
+//! }
+//!
+//! let data: Data = serde_json::from_str(r#"{"ids": ["1", 2.0], "flag": "yes"}"#).unwrap();
+//! assert_eq!(data.ids, [1, 2]);
+//! assert_eq!(data.flag, Some(true));
+//! ```
+//!
+//! `Option<AsU64>` uses serde's own `null` handling. Use `AsU64Opt` for null sentinels like `"none"`.
+
+use crate::config::Config;
+use crate::{FlexibleParse, Num};
+use crate::normalize::Normalize;
+use crate::patch::Patch;
+use crate::sentinels::NullSentinels;
+use serde::{Deserializer, Serialize, Serializer};
+use serde_with::{DeserializeAs, SerializeAs};
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::marker::PhantomData;
+
+macro_rules! markers {
+    ($($marker:ident: $ty:ty => $de:ident),+ $(,)?) => {
+        $(
+            #[doc = concat!("Deserializes `", stringify!($ty), "` with [`", stringify!($de), "`](crate::", stringify!($de), ").")]
+            pub struct $marker;
+
+            impl<'de> DeserializeAs<'de, $ty> for $marker {
+                fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<$ty, D::Error> {
+                    crate::$de(deserializer)
+                }
+            }
+
+            impl<'de> SerializeAs<$ty> for $marker {
+                fn serialize_as<S: Serializer>(source: &$ty, serializer: S) -> Result<S::Ok, S::Error> {
+                    source.serialize(serializer)
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! generic_markers {
+    ($($marker:ident<$param:ident: $bound:path>: $ty:ty => $de:ident),+ $(,)?) => {
+        $(
+            #[doc = concat!("Deserializes `", stringify!($ty), "` with [`", stringify!($de), "`](crate::", stringify!($de), ").")]
+            pub struct $marker<$param>(PhantomData<$param>);
+
+            impl<'de, $param: $bound> DeserializeAs<'de, $ty> for $marker<$param> {
+                fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<$ty, D::Error> {
+                    crate::$de::<$param, D>(deserializer)
+                }
+            }
+
+            impl<'de, $param: $bound> SerializeAs<$ty> for $marker<$param> {
+                fn serialize_as<Ser: Serializer>(source: &$ty, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+                    source.serialize(serializer)
+                }
+            }
+        )+
+    };
+}
+
+macro_rules! const_markers {
+    ($($marker:ident<$c:ident: $cty:ty>: $ty:ty => $de:ident),+ $(,)?) => {
+        $(
+            #[doc = concat!("Deserializes `", stringify!($ty), "` with [`", stringify!($de), "`](crate::", stringify!($de), ").")]
+            pub struct $marker<const $c: $cty>;
+
+            impl<'de, const $c: $cty> DeserializeAs<'de, $ty> for $marker<$c> {
+                fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<$ty, D::Error> {
+                    crate::$de::<$c, D>(deserializer)
+                }
+            }
+
+            impl<const $c: $cty> SerializeAs<$ty> for $marker<$c> {
+                fn serialize_as<S: Serializer>(source: &$ty, serializer: S) -> Result<S::Ok, S::Error> {
+                    source.serialize(serializer)
+                }
+            }
+        )+
+    };
+}
+
+markers! {
+    AsBool: bool => as_bool,
+    AsChar: char => as_char,
+    AsCowStr: Cow<'de, str> => as_cow_str,
+    AsF64: f64 => as_f64,
+    AsI64: i64 => as_i64,
+    AsString: String => as_string,
+    AsStringLossy: String => as_string_lossy,
+    AsU64: u64 => as_u64,
+
+    AsBoolOpt: Option<bool> => as_bool_opt,
+    AsCharOpt: Option<char> => as_char_opt,
+    AsCowStrOpt: Option<Cow<'de, str>> => as_cow_str_opt,
+    AsF64Opt: Option<f64> => as_f64_opt,
+    AsI64Opt: Option<i64> => as_i64_opt,
+    AsStringOpt: Option<String> => as_string_opt,
+    AsStringOptNonEmpty: Option<String> => as_string_opt_non_empty,
+    AsStringOptNonBlank: Option<String> => as_string_opt_non_blank,
+    AsStringOptSentinel: Option<String> => as_string_opt_sentinel,
+    AsU64Opt: Option<u64> => as_u64_opt,
+
+    AsBoolLenient: Option<bool> => as_bool_lenient,
+    AsCharLenient: Option<char> => as_char_lenient,
+    AsF64Lenient: Option<f64> => as_f64_lenient,
+    AsI64Lenient: Option<i64> => as_i64_lenient,
+    AsU64Lenient: Option<u64> => as_u64_lenient,
+
+    AsBoolOrDefault: bool => as_bool_or_default,
+    AsF64OrDefault: f64 => as_f64_or_default,
+    AsI64OrDefault: i64 => as_i64_or_default,
+    AsU64OrDefault: u64 => as_u64_or_default,
+
+    AsBoolPatch: Patch<bool> => as_bool_patch,
+    AsCharPatch: Patch<char> => as_char_patch,
+    AsF64Patch: Patch<f64> => as_f64_patch,
+    AsI64Patch: Patch<i64> => as_i64_patch,
+    AsStringPatch: Patch<String> => as_string_patch,
+    AsU64Patch: Patch<u64> => as_u64_patch,
+}
+
+generic_markers! {
+    AsStringNormalized<N: Normalize>: String => as_string_normalized,
+    AsStringNormalizedOpt<N: Normalize>: Option<String> => as_string_normalized_opt,
+
+    AsBoolOptWith<S: NullSentinels>: Option<bool> => as_bool_opt_with,
+    AsCharOptWith<S: NullSentinels>: Option<char> => as_char_opt_with,
+    AsCowStrOptWith<S: NullSentinels>: Option<Cow<'de, str>> => as_cow_str_opt_with,
+    AsF64OptWith<S: NullSentinels>: Option<f64> => as_f64_opt_with,
+    AsI64OptWith<S: NullSentinels>: Option<i64> => as_i64_opt_with,
+    AsStringOptWith<S: NullSentinels>: Option<String> => as_string_opt_with,
+    AsU64OptWith<S: NullSentinels>: Option<u64> => as_u64_opt_with,
+
+    AsBoolConfig<C: Config>: bool => as_bool_config,
+    AsCharConfig<C: Config>: char => as_char_config,
+    AsF64Config<C: Config>: f64 => as_f64_config,
+    AsI64Config<C: Config>: i64 => as_i64_config,
+    AsU64Config<C: Config>: u64 => as_u64_config,
+    AsBoolOptConfig<C: Config>: Option<bool> => as_bool_opt_config,
+    AsCharOptConfig<C: Config>: Option<char> => as_char_opt_config,
+    AsF64OptConfig<C: Config>: Option<f64> => as_f64_opt_config,
+    AsI64OptConfig<C: Config>: Option<i64> => as_i64_opt_config,
+    AsU64OptConfig<C: Config>: Option<u64> => as_u64_opt_config,
+}
+
+const_markers! {
+    AsBoolOr<V: bool>: bool => as_bool_or,
+    AsI64Or<V: i64>: i64 => as_i64_or,
+    AsU64Or<V: u64>: u64 => as_u64_or,
+}
+
+/// Deserializes any [`Num`] type `T` with [`as_num::<T, P, _>`](crate::as_num).
+pub struct AsNum<P>(PhantomData<P>);
+
+/// Deserializes `Option<T>` for any [`Num`] type `T` with [`as_num_opt::<T, P, _>`](crate::as_num_opt).
+pub struct AsNumOpt<P>(PhantomData<P>);
+
+impl<'de, T: Num, P: Config> DeserializeAs<'de, T> for AsNum<P> {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        crate::as_num::<T, P, D>(deserializer)
+    }
+}
+
+impl<T: Num + Serialize, P: Config> SerializeAs<T> for AsNum<P> {
+    fn serialize_as<S: Serializer>(source: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        source.serialize(serializer)
+    }
+}
+
+impl<'de, T: Num, P: Config> DeserializeAs<'de, Option<T>> for AsNumOpt<P> {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error> {
+        crate::as_num_opt::<T, P, D>(deserializer)
+    }
+}
+
+impl<T: Num + Serialize, P: Config> SerializeAs<Option<T>> for AsNumOpt<P> {
+    fn serialize_as<S: Serializer>(source: &Option<T>, serializer: S) -> Result<S::Ok, S::Error> {
+        source.serialize(serializer)
+    }
+}
+
+/// Deserializes any [`FlexibleParse`] type with [`as_flexible`](crate::as_flexible).
+pub struct AsFlexible;
+
+/// Deserializes `Option<T>` for any [`FlexibleParse`] type `T` with [`as_flexible_opt`](crate::as_flexible_opt).
+pub struct AsFlexibleOpt;
+
+impl<'de, T: FlexibleParse> DeserializeAs<'de, T> for AsFlexible {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+        crate::as_flexible::<T, D>(deserializer)
+    }
+}
+
+impl<T: FlexibleParse + Serialize> SerializeAs<T> for AsFlexible {
+    fn serialize_as<S: Serializer>(source: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        source.serialize(serializer)
+    }
+}
+
+impl<'de, T: FlexibleParse> DeserializeAs<'de, Option<T>> for AsFlexibleOpt {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Option<T>, D::Error> {
+        crate::as_flexible_opt::<T, D>(deserializer)
+    }
+}
+
+impl<T: FlexibleParse + Serialize> SerializeAs<Option<T>> for AsFlexibleOpt {
+    fn serialize_as<S: Serializer>(source: &Option<T>, serializer: S) -> Result<S::Ok, S::Error> {
+        source.serialize(serializer)
+    }
+}
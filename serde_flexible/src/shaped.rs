@@ -0,0 +1,187 @@
+use crate::{as_bool, as_f64, as_i64, as_u64};
+use serde::de::{EnumAccess, Error, MapAccess, SeqAccess, Visitor};
+use serde::{forward_to_deserialize_any, Deserialize, Deserializer, Serialize, Serializer};
+use core::cell::Cell;
+use core::fmt;
+
+/// How a [`Shaped`] value was written in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Shape {
+    /// A number or a bool, e.g. `42`.
+    #[default]
+    Native,
+    /// A string or byte string, e.g. `"42"`.
+    String,
+}
+
+/// A value deserialized with the matching flexible deserializer that serializes in the shape
+/// it was read in, for proxies that re-emit loose data unchanged.
+///
+/// `Shaped<u64>` deserializes with `as_u64`. If the input was `"42"` it serializes as `"42"`,
+/// if it was `42` or `42.0` it serializes as `42`. Only self-describing formats can read both
+/// shapes back: with [`TypeHints::Always`](crate::config::TypeHints::Always), e.g. for bincode,
+/// a value read from a string fails to deserialize again.
+///
+/// ```
+/// use serde_flexible::{Shape, Shaped};
+///
+/// let ids: Vec<Shaped<u64>> = serde_json::from_str(r#"[1, "2"]"#).unwrap();
+/// assert_eq!(ids[1], Shaped { value: 2, shape: Shape::String });
+/// assert_eq!(serde_json::to_string(&ids).unwrap(), r#"[1,"2"]"#);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Shaped<T> {
+    pub value: T,
+    pub shape: Shape,
+}
+
+impl<T> Shaped<T> {
+    pub fn new(value: T, shape: Shape) -> Self {
+        Shaped { value, shape }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Serialize + fmt::Display> Serialize for Shaped<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.shape {
+            Shape::Native => self.value.serialize(serializer),
+            Shape::String => serializer.collect_str(&self.value),
+        }
+    }
+}
+
+macro_rules! impl_shaped {
+    ($($ty:ty => $de:ident),+ $(,)?) => {
+        $(
+            impl<'de> Deserialize<'de> for Shaped<$ty> {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    let shape = Cell::new(Shape::Native);
+                    let value = $de(ShapeDeserializer { deserializer, shape: &shape })?;
+                    Ok(Shaped { value, shape: shape.get() })
+                }
+            }
+        )+
+    };
+}
+
+impl_shaped! {
+    bool => as_bool,
+    f64 => as_f64,
+    i64 => as_i64,
+    u64 => as_u64,
+}
+
+/// Records in `shape` whether the wrapped deserializer produced a string.
+struct ShapeDeserializer<'a, D> {
+    deserializer: D,
+    shape: &'a Cell<Shape>,
+}
+
+// the type hinted methods are passed on for formats that are not self-describing
+macro_rules! forward_with_shape {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                self.deserializer.$method(ShapeVisitor { visitor, shape: self.shape })
+            }
+        )*
+    };
+}
+
+impl<'de, D: Deserializer<'de>> Deserializer<'de> for ShapeDeserializer<'_, D> {
+    type Error = D::Error;
+
+    fn is_human_readable(&self) -> bool {
+        self.deserializer.is_human_readable()
+    }
+
+    forward_with_shape! {
+        deserialize_any deserialize_bool deserialize_i64 deserialize_u64 deserialize_f64
+    }
+
+    forward_to_deserialize_any! {
+        i8 i16 i32 i128 u8 u16 u32 u128 f32 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct ShapeVisitor<'a, V> {
+    visitor: V,
+    shape: &'a Cell<Shape>,
+}
+
+impl<'de, V: Visitor<'de>> ShapeVisitor<'_, V> {
+    fn string(self) -> V {
+        self.shape.set(Shape::String);
+        self.visitor
+    }
+}
+
+impl<'de, V: Visitor<'de>> Visitor<'de> for ShapeVisitor<'_, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    fn visit_bool<E: Error>(self, v: bool) -> Result<Self::Value, E> { self.visitor.visit_bool(v) }
+    fn visit_i64<E: Error>(self, v: i64) -> Result<Self::Value, E> { self.visitor.visit_i64(v) }
+    fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> { self.visitor.visit_u64(v) }
+    fn visit_f64<E: Error>(self, v: f64) -> Result<Self::Value, E> { self.visitor.visit_f64(v) }
+    fn visit_char<E: Error>(self, v: char) -> Result<Self::Value, E> { self.string().visit_char(v) }
+    fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> { self.string().visit_str(v) }
+    fn visit_borrowed_str<E: Error>(self, v: &'de str) -> Result<Self::Value, E> { self.string().visit_borrowed_str(v) }
+    #[cfg(feature = "alloc")]
+    fn visit_string<E: Error>(self, v: alloc::string::String) -> Result<Self::Value, E> { self.string().visit_string(v) }
+    fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> { self.string().visit_bytes(v) }
+    fn visit_borrowed_bytes<E: Error>(self, v: &'de [u8]) -> Result<Self::Value, E> { self.string().visit_borrowed_bytes(v) }
+    #[cfg(feature = "alloc")]
+    fn visit_byte_buf<E: Error>(self, v: alloc::vec::Vec<u8>) -> Result<Self::Value, E> { self.string().visit_byte_buf(v) }
+    fn visit_none<E: Error>(self) -> Result<Self::Value, E> { self.visitor.visit_none() }
+    fn visit_unit<E: Error>(self) -> Result<Self::Value, E> { self.visitor.visit_unit() }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.visitor.visit_some(deserializer)
+    }
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.visitor.visit_newtype_struct(deserializer)
+    }
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.visitor.visit_seq(seq)
+    }
+    fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.visitor.visit_map(map)
+    }
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.visitor.visit_enum(data)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let v: Vec<Shaped<u64>> = serde_json::from_str(r#"[1, "2", 3.0, "4.0"]"#).unwrap();
+        assert_eq!(v.iter().map(|v| v.value).collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert_eq!(serde_json::to_string(&v).unwrap(), r#"[1,"2",3,"4"]"#);
+
+        let v: (Shaped<bool>, Shaped<bool>, Shaped<i64>, Shaped<f64>) = serde_json::from_str(r#"["yes", false, "-1", 2.5]"#).unwrap();
+        assert_eq!(v.0, Shaped::new(true, Shape::String));
+        assert_eq!(v.1, Shaped::new(false, Shape::Native));
+        assert_eq!(serde_json::to_string(&v).unwrap(), r#"["true",false,"-1",2.5]"#);
+    }
+
+    #[test]
+    fn test_base_error() {
+        assert!(serde_json::from_str::<Shaped<u64>>(r#""x""#).is_err());
+        assert!(serde_json::from_str::<Shaped<bool>>(r#"null"#).is_err());
+    }
+}
@@ -0,0 +1,424 @@
+//! Modules for `#[serde(with = "...")]`, pairing each deserializer with a serializer.
+//!
+//! `serde_flexible::u64` deserializes with `as_u64` and serializes a number,
+//! `serde_flexible::u64::string` deserializes the same way and serializes a string.
+//! `opt`, `lenient`, `or_default` and `patch` follow the matching deserializers.
+//! `patch` serializes like [`Patch<T>`](crate::patch::Patch), which fails for `Patch::Missing`,
+//! so pair it with `#[serde(default, skip_serializing_if = "Patch::is_missing")]`.
+//! `u64::js_safe` and `i64::js_safe` serialize a string only above `2^53 - 1`, where JavaScript
+//! numbers lose precision.
+//! The modules are re-exported from the crate root.
+//!
+//! The generic deserializers (`as_u64_opt_with`, `as_u64_or`, `as_string_normalized`, ...) have no
+//! module. Combine them with the `serialize` function of the matching one instead, e.g.
+//! `#[serde(deserialize_with = "as_u64_opt_with::<S, _>", serialize_with = "serde_flexible::u64::opt::serialize")]`.
+
+use core::fmt::Display;
+
+/// Serializes `T` with `collect_str`.
+struct Stringified<'a, T>(&'a T);
+
+impl<T: Display> serde::Serialize for Stringified<'_, T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self.0)
+    }
+}
+
+// The modules below are named after primitive types, so the macros only use absolute paths.
+
+macro_rules! native {
+    ($ty:ty => $de:path) => {
+        pub fn deserialize<'de, D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<$ty, D::Error> {
+            $de(deserializer)
+        }
+
+        pub fn serialize<S: ::serde::Serializer>(v: &$ty, serializer: S) -> Result<S::Ok, S::Error> {
+            ::serde::Serialize::serialize(v, serializer)
+        }
+    };
+}
+
+// With type hints the string is read with `deserialize_str`, because `$de` would ask the format for a number.
+macro_rules! from_string {
+    ($ty:ty => $de:path) => {
+        struct FromString;
+
+        impl<'de> ::serde::de::Visitor<'de> for FromString {
+            type Value = $ty;
+
+            fn expecting(&self, formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E: ::serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                $de(::serde::de::value::StrDeserializer::<E>::new(v))
+            }
+        }
+    };
+}
+
+macro_rules! stringified {
+    ($ty:ty => $de:path) => {
+        from_string!($ty => $de);
+
+        pub fn deserialize<'de, D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<$ty, D::Error> {
+            if $crate::config::config().hinted() {
+                deserializer.deserialize_str(FromString)
+            } else {
+                $de(deserializer)
+            }
+        }
+
+        pub fn serialize<S: ::serde::Serializer>(v: &$ty, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(v)
+        }
+    };
+}
+
+macro_rules! stringified_opt {
+    ($ty:ty => $de:path, $opt:path) => {
+        from_string!($ty => $de);
+
+        struct OptFromString;
+
+        impl<'de> ::serde::de::Visitor<'de> for OptFromString {
+            type Value = Option<$ty>;
+
+            fn expecting(&self, formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                formatter.write_str("null or a string")
+            }
+
+            fn visit_none<E: ::serde::de::Error>(self) -> Result<Self::Value, E> { Ok(None) }
+            fn visit_unit<E: ::serde::de::Error>(self) -> Result<Self::Value, E> { Ok(None) }
+
+            fn visit_some<D: ::serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+                deserializer.deserialize_str(FromString).map(Some)
+            }
+        }
+
+        pub fn deserialize<'de, D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Option<$ty>, D::Error> {
+            if $crate::config::config().hinted() {
+                deserializer.deserialize_option(OptFromString)
+            } else {
+                $opt(deserializer)
+            }
+        }
+
+        pub fn serialize<S: ::serde::Serializer>(v: &Option<$ty>, serializer: S) -> Result<S::Ok, S::Error> {
+            match v {
+                Some(v) => serializer.serialize_some(&$crate::with::Stringified(v)),
+                None => serializer.serialize_none(),
+            }
+        }
+    };
+}
+
+/// The largest integer a JavaScript `Number` represents exactly, `2^53 - 1`.
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Serializes integers as numbers if JavaScript can represent them exactly, and as strings otherwise.
+trait JsSafe: Display + serde::Serialize {
+    fn is_js_safe(&self) -> bool;
+
+    fn serialize_js_safe<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.is_js_safe() {
+            self.serialize(serializer)
+        } else {
+            serializer.collect_str(self)
+        }
+    }
+}
+
+impl JsSafe for u64 {
+    fn is_js_safe(&self) -> bool { *self <= MAX_SAFE_INTEGER }
+}
+
+impl JsSafe for i64 {
+    fn is_js_safe(&self) -> bool { self.unsigned_abs() <= MAX_SAFE_INTEGER }
+}
+
+struct JsSafeValue<'a, T>(&'a T);
+
+impl<T: JsSafe> serde::Serialize for JsSafeValue<'_, T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize_js_safe(serializer)
+    }
+}
+
+macro_rules! js_safe {
+    ($ty:ident, $de:ident, $opt:ident) => {
+        #[doc = concat!("Deserialized with [`", stringify!($de), "`](crate::", stringify!($de), "), serialized as a number up to")]
+        /// `2^53 - 1` in magnitude and as a string above, so JavaScript clients keep every digit.
+        pub mod js_safe {
+            pub fn deserialize<'de, D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<$ty, D::Error> {
+                $crate::$de(deserializer)
+            }
+
+            pub fn serialize<S: ::serde::Serializer>(v: &$ty, serializer: S) -> Result<S::Ok, S::Error> {
+                $crate::with::JsSafe::serialize_js_safe(v, serializer)
+            }
+        }
+
+        #[doc = concat!("Like [`js_safe`], for `Option<", stringify!($ty), ">` with [`", stringify!($opt), "`](crate::", stringify!($opt), ").")]
+        pub mod opt_js_safe {
+            pub fn deserialize<'de, D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Option<$ty>, D::Error> {
+                $crate::$opt(deserializer)
+            }
+
+            pub fn serialize<S: ::serde::Serializer>(v: &Option<$ty>, serializer: S) -> Result<S::Ok, S::Error> {
+                match v {
+                    Some(v) => serializer.serialize_some(&$crate::with::JsSafeValue(v)),
+                    None => serializer.serialize_none(),
+                }
+            }
+        }
+    };
+}
+
+macro_rules! number_module {
+    ($name:ident: $ty:ident, $de:ident, $opt:ident, $lenient:ident, $or_default:ident, $patch:ident $(, $extra:ident)?) => {
+        #[doc = concat!("`", stringify!($ty), "` fields: deserialized with [`", stringify!($de), "`](crate::", stringify!($de), "), serialized as is.")]
+        pub mod $name {
+            native!($ty => $crate::$de);
+
+            #[doc = concat!("Deserialized with [`", stringify!($de), "`](crate::", stringify!($de), "), serialized as a string.")]
+            pub mod string {
+                stringified!($ty => $crate::$de);
+            }
+
+            #[doc = concat!("Deserialized with [`", stringify!($opt), "`](crate::", stringify!($opt), "), serialized as is.")]
+            pub mod opt {
+                native!(Option<$ty> => $crate::$opt);
+
+                #[doc = concat!("Deserialized with [`", stringify!($opt), "`](crate::", stringify!($opt), "), serialized as a string or `null`.")]
+                pub mod string {
+                    stringified_opt!($ty => $crate::$de, $crate::$opt);
+                }
+            }
+
+            #[doc = concat!("Deserialized with [`", stringify!($lenient), "`](crate::", stringify!($lenient), "), serialized as is.")]
+            pub mod lenient {
+                native!(Option<$ty> => $crate::$lenient);
+            }
+
+            #[doc = concat!("Deserialized with [`", stringify!($or_default), "`](crate::", stringify!($or_default), "), serialized as is.")]
+            pub mod or_default {
+                native!($ty => $crate::$or_default);
+            }
+
+            #[doc = concat!("Deserialized with [`", stringify!($patch), "`](crate::", stringify!($patch), "), serialized as is.")]
+            ///
+            /// Serializing `Patch::Missing` fails, skip it with `skip_serializing_if = "Patch::is_missing"`.
+            pub mod patch {
+                native!($crate::patch::Patch<$ty> => $crate::$patch);
+            }
+
+            $($extra!($ty, $de, $opt);)?
+        }
+    };
+}
+
+number_module!(bool: bool, as_bool, as_bool_opt, as_bool_lenient, as_bool_or_default, as_bool_patch);
+number_module!(f64: f64, as_f64, as_f64_opt, as_f64_lenient, as_f64_or_default, as_f64_patch);
+number_module!(i64: i64, as_i64, as_i64_opt, as_i64_lenient, as_i64_or_default, as_i64_patch, js_safe);
+number_module!(u64: u64, as_u64, as_u64_opt, as_u64_lenient, as_u64_or_default, as_u64_patch, js_safe);
+
+/// `char` fields: deserialized with [`as_char`](crate::as_char), serialized as is.
+pub mod char {
+    native!(char => crate::as_char);
+
+    /// Deserialized with [`as_char_opt`](crate::as_char_opt), serialized as is.
+    pub mod opt {
+        native!(Option<char> => crate::as_char_opt);
+    }
+
+    /// Deserialized with [`as_char_lenient`](crate::as_char_lenient), serialized as is.
+    pub mod lenient {
+        native!(Option<char> => crate::as_char_lenient);
+    }
+
+    /// Deserialized with [`as_char_patch`](crate::as_char_patch), serialized as is.
+    ///
+    /// Serializing `Patch::Missing` fails, skip it with `skip_serializing_if = "Patch::is_missing"`.
+    pub mod patch {
+        native!(crate::patch::Patch<char> => crate::as_char_patch);
+    }
+}
+
+/// `String` fields: deserialized with [`as_string`](crate::as_string), serialized as is.
+#[cfg(feature = "alloc")]
+pub mod string {
+    native!(alloc::string::String => crate::as_string);
+
+    /// Deserialized with [`as_string_lossy`](crate::as_string_lossy), serialized as is.
+    pub mod lossy {
+        native!(alloc::string::String => crate::as_string_lossy);
+    }
+
+    /// Deserialized with [`as_string_opt`](crate::as_string_opt), serialized as is.
+    pub mod opt {
+        native!(Option<alloc::string::String> => crate::as_string_opt);
+    }
+
+    /// Deserialized with [`as_string_opt_non_empty`](crate::as_string_opt_non_empty), serialized as is.
+    pub mod opt_non_empty {
+        native!(Option<alloc::string::String> => crate::as_string_opt_non_empty);
+    }
+
+    /// Deserialized with [`as_string_opt_non_blank`](crate::as_string_opt_non_blank), serialized as is.
+    pub mod opt_non_blank {
+        native!(Option<alloc::string::String> => crate::as_string_opt_non_blank);
+    }
+
+    /// Deserialized with [`as_string_opt_sentinel`](crate::as_string_opt_sentinel), serialized as is.
+    pub mod opt_sentinel {
+        native!(Option<alloc::string::String> => crate::as_string_opt_sentinel);
+    }
+
+    /// Deserialized with [`as_string_patch`](crate::as_string_patch), serialized as is.
+    ///
+    /// Serializing `Patch::Missing` fails, skip it with `skip_serializing_if = "Patch::is_missing"`.
+    pub mod patch {
+        native!(crate::patch::Patch<alloc::string::String> => crate::as_string_patch);
+    }
+}
+
+/// `Cow<str>` fields: deserialized with [`as_cow_str`](crate::as_cow_str), serialized as is.
+/// Use it together with `#[serde(borrow)]`.
+#[cfg(feature = "alloc")]
+pub mod cow_str {
+    use serde::{Deserializer, Serializer};
+    use alloc::borrow::Cow;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Cow<'de, str>, D::Error> {
+        crate::as_cow_str(deserializer)
+    }
+
+    pub fn serialize<S: Serializer>(v: &str, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(v)
+    }
+
+    /// Deserialized with [`as_cow_str_opt`](crate::as_cow_str_opt), serialized as is.
+    pub mod opt {
+        use serde::{Deserializer, Serialize, Serializer};
+        use alloc::borrow::Cow;
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Cow<'de, str>>, D::Error> {
+            crate::as_cow_str_opt(deserializer)
+        }
+
+        pub fn serialize<S: Serializer>(v: &Option<Cow<'_, str>>, serializer: S) -> Result<S::Ok, S::Error> {
+            v.serialize(serializer)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::patch::Patch;
+    use serde::{Deserialize, Serialize};
+    use alloc::borrow::Cow;
+
+    #[derive(Debug, Deserialize, Serialize, PartialEq)]
+    struct Test<'a> {
+        #[serde(with = "super::u64")]
+        id: u64,
+        #[serde(with = "super::i64::string")]
+        delta: i64,
+        #[serde(with = "super::f64::opt")]
+        price: Option<f64>,
+        #[serde(with = "super::u64::opt::string")]
+        limit: Option<u64>,
+        #[serde(with = "super::bool::lenient")]
+        active: Option<bool>,
+        #[serde(with = "super::u64::or_default")]
+        retries: u64,
+        #[serde(default, with = "super::u64::patch", skip_serializing_if = "Patch::is_missing")]
+        age: Patch<u64>,
+        #[serde(with = "super::char")]
+        letter: char,
+        #[serde(with = "super::string::opt_non_blank")]
+        note: Option<String>,
+        #[serde(borrow, with = "super::cow_str")]
+        code: Cow<'a, str>,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let v: Test = serde_json::from_str(r#"{
+            "id": "7", "delta": -3, "price": "1.5", "limit": 10, "active": "maybe",
+            "retries": "x", "letter": 65, "note": " ", "code": 12
+        }"#).unwrap();
+        assert_eq!(
+            v,
+            Test {
+                id: 7, delta: -3, price: Some(1.5), limit: Some(10), active: None, retries: 0, age: Patch::Missing,
+                letter: 'A', note: None, code: Cow::Owned("12".to_string()),
+            }
+        );
+        assert_eq!(
+            serde_json::to_string(&v).unwrap(),
+            r#"{"id":7,"delta":"-3","price":1.5,"limit":"10","active":null,"retries":0,"letter":"A","note":null,"code":"12"}"#
+        );
+
+        let json = serde_json::to_string(&v).unwrap();
+        let v: Test = serde_json::from_str(&json).unwrap();
+        assert_eq!((v.delta, v.limit), (-3, Some(10)));
+    }
+
+    #[test]
+    fn test_patch() {
+        #[derive(Serialize)]
+        struct Unskipped {
+            #[serde(with = "super::u64::patch")]
+            age: Patch<u64>,
+        }
+
+        assert!(serde_json::to_string(&Unskipped { age: Patch::Missing }).is_err());
+        assert_eq!(serde_json::to_string(&Unskipped { age: Patch::Null }).unwrap(), r#"{"age":null}"#);
+
+        #[derive(Debug, Deserialize, Serialize, PartialEq)]
+        struct Skipped {
+            #[serde(default, with = "super::u64::patch", skip_serializing_if = "Patch::is_missing")]
+            age: Patch<u64>,
+        }
+
+        assert_eq!(serde_json::to_string(&Skipped { age: Patch::Missing }).unwrap(), "{}");
+        assert_eq!(serde_json::to_string(&Skipped { age: Patch::Value(3) }).unwrap(), r#"{"age":3}"#);
+        assert_eq!(serde_json::from_str::<Skipped>("{}").unwrap(), Skipped { age: Patch::Missing });
+    }
+
+    #[test]
+    fn test_js_safe() {
+        #[derive(Debug, Deserialize, Serialize, PartialEq)]
+        struct Test {
+            #[serde(with = "super::u64::js_safe")]
+            id: u64,
+            #[serde(with = "super::i64::js_safe")]
+            delta: i64,
+            #[serde(with = "super::u64::opt_js_safe")]
+            parent: Option<u64>,
+        }
+
+        let to_json = |id, delta, parent| serde_json::to_string(&Test { id, delta, parent }).unwrap();
+        assert_eq!(to_json(9007199254740991, -9007199254740991, None), r#"{"id":9007199254740991,"delta":-9007199254740991,"parent":null}"#);
+        assert_eq!(to_json(9007199254740992, -9007199254740992, Some(1)), r#"{"id":"9007199254740992","delta":"-9007199254740992","parent":1}"#);
+        assert_eq!(to_json(u64::MAX, i64::MIN, Some(u64::MAX)), r#"{"id":"18446744073709551615","delta":"-9223372036854775808","parent":"18446744073709551615"}"#);
+
+        let v = Test { id: u64::MAX, delta: i64::MIN, parent: Some(9007199254740993) };
+        assert_eq!(serde_json::from_str::<Test>(&serde_json::to_string(&v).unwrap()).unwrap(), v);
+    }
+
+    #[test]
+    fn test_stringified_opt_none() {
+        #[derive(Serialize)]
+        struct Test {
+            #[serde(with = "super::f64::opt::string")]
+            v: Option<f64>,
+        }
+
+        assert_eq!(serde_json::to_string(&Test { v: None }).unwrap(), r#"{"v":null}"#);
+        assert_eq!(serde_json::to_string(&Test { v: Some(2.5) }).unwrap(), r#"{"v":"2.5"}"#);
+    }
+}
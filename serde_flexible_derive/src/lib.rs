@@ -0,0 +1,348 @@
+//! Attribute macro for `serde-flexible`, re-exported as `serde_flexible::flexible` with the
+//! `derive` feature.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2, TokenTree};
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Field, Fields, GenericArgument, Path, PathArguments, Token, Type};
+
+/// Picks a flexible deserializer for every field from its type.
+///
+/// A field without `deserialize_with` or `with` gets the matching function: `u64` uses
+/// `as_u64`, `Option<bool>` uses `as_bool_opt`, `Patch<f64>` uses `as_f64_patch`, and so on for
+/// `bool`, `char`, `f64`, `i64`, `u64`, `String` and `Cow<str>`. The other number types use
+/// `as_num` and `as_num_opt`, e.g. `u16` uses `as_num::<u16, GlobalConfig, _>`, but they have
+/// no `Patch<T>` deserializer and no field attributes. Fields of other types are left alone.
+/// `Option<T>` and `Patch<T>` fields also get `#[serde(default)]`, because serde only treats an
+/// absent field as `None` if it deserializes the field itself. The same applies to fields that
+/// already have a `deserialize_with` or `with` attribute. `Cow<'a, str>` fields also get
+/// `#[serde(borrow)]`, so they borrow from the input when the format allows it.
+///
+/// A field attribute changes the choice:
+///
+/// - `#[flexible(skip)]`: leave the field alone.
+/// - `#[flexible(lenient)]`: `as_*_lenient` for `Option<T>`.
+/// - `#[flexible(or_default)]`: `as_*_or_default`, also used for absent fields.
+/// - `#[flexible(sentinels = Type)]`: `as_*_opt_with::<Type, _>` for `Option<T>`.
+/// - `#[flexible(non_empty)]`, `#[flexible(non_blank)]`, `#[flexible(sentinel)]`: the matching
+///   `as_string_opt_*` for `Option<String>`.
+/// - `#[flexible(lossy)]`: `as_string_lossy` for `String`.
+///
+/// The generated paths start with `serde_flexible::`. Place the attribute above
+/// `#[derive(Deserialize)]`:
+///
+/// ```ignore
+/// #[flexible]
+/// #[derive(Deserialize)]
+/// struct Message {
+///     id: u64,            // as_u64
+///     price: Option<f64>, // as_f64_opt, absent => None
+///     #[flexible(skip)]
+///     raw: String,        // plain serde
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn flexible(args: TokenStream, input: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        let args = TokenStream2::from(args);
+        return syn::Error::new_spanned(args, "#[flexible] takes no arguments").to_compile_error().into();
+    }
+
+    let mut input = parse_macro_input!(input as DeriveInput);
+    let result = match &mut input.data {
+        Data::Struct(data) => expand_fields(&mut data.fields),
+        Data::Enum(data) => data.variants.iter_mut().try_for_each(|v| expand_fields(&mut v.fields)),
+        Data::Union(data) => Err(syn::Error::new(data.union_token.span, "#[flexible] does not support unions")),
+    };
+    match result {
+        Ok(()) => quote!(#input).into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand_fields(fields: &mut Fields) -> syn::Result<()> {
+    let mut result = Ok(());
+    for field in fields.iter_mut() {
+        if let Err(err) = expand_field(field) {
+            combine(&mut result, err);
+        }
+    }
+    result
+}
+
+fn expand_field(field: &mut Field) -> syn::Result<()> {
+    let policy = take_policy(&mut field.attrs)?;
+    let keys = serde_keys(&field.attrs);
+    let has = |key: &str| keys.iter().any(|k| k == key);
+    let custom = has("deserialize_with") || has("with");
+
+    let (function, default) = match &policy {
+        Some((Policy::Skip, _)) => return Ok(()),
+        Some((_, span)) if custom => {
+            return Err(syn::Error::new(*span, "#[flexible(...)] conflicts with `deserialize_with` and `with`"));
+        }
+        _ if custom => (None, is_optional(&field.ty)),
+        _ if has("skip") || has("skip_deserializing") || has("flatten") => return Ok(()),
+        Some((policy, span)) => match select(policy, &field.ty) {
+            Some(selected) => selected,
+            None => return Err(syn::Error::new(*span, format!("#[flexible({})] is not supported for this type", policy.name()))),
+        },
+        None => match select(&Policy::Infer, &field.ty) {
+            Some(selected) => selected,
+            None => return Ok(()),
+        },
+    };
+
+    if let Some(function) = function {
+        field.attrs.push(parse_quote!(#[serde(deserialize_with = #function)]));
+        if borrows(&field.ty) && !has("borrow") {
+            field.attrs.push(parse_quote!(#[serde(borrow)]));
+        }
+    }
+    if default && !has("default") {
+        field.attrs.push(parse_quote!(#[serde(default)]));
+    }
+    Ok(())
+}
+
+enum Policy {
+    Infer,
+    Skip,
+    Lenient,
+    OrDefault,
+    Sentinels(Path),
+    NonEmpty,
+    NonBlank,
+    Sentinel,
+    Lossy,
+}
+
+impl Policy {
+    fn name(&self) -> &'static str {
+        match self {
+            Policy::Infer => "",
+            Policy::Skip => "skip",
+            Policy::Lenient => "lenient",
+            Policy::OrDefault => "or_default",
+            Policy::Sentinels(_) => "sentinels",
+            Policy::NonEmpty => "non_empty",
+            Policy::NonBlank => "non_blank",
+            Policy::Sentinel => "sentinel",
+            Policy::Lossy => "lossy",
+        }
+    }
+}
+
+/// Removes the `#[flexible(...)]` field attributes and returns the policy they set.
+fn take_policy(attrs: &mut Vec<Attribute>) -> syn::Result<Option<(Policy, Span)>> {
+    let mut policy = None;
+    let mut result = Ok(());
+    attrs.retain(|attr| {
+        if !attr.path().is_ident("flexible") {
+            return true;
+        }
+        let parsed = attr.parse_nested_meta(|meta| {
+            let parsed = match meta.path.get_ident().map(|i| i.to_string()).as_deref() {
+                Some("skip") => Policy::Skip,
+                Some("lenient") => Policy::Lenient,
+                Some("or_default") => Policy::OrDefault,
+                Some("sentinels") => Policy::Sentinels(meta.value()?.parse()?),
+                Some("non_empty") => Policy::NonEmpty,
+                Some("non_blank") => Policy::NonBlank,
+                Some("sentinel") => Policy::Sentinel,
+                Some("lossy") => Policy::Lossy,
+                _ => return Err(meta.error("unknown #[flexible] option")),
+            };
+            if policy.is_some() {
+                return Err(meta.error("only one #[flexible] option is allowed per field"));
+            }
+            policy = Some((parsed, meta.path.span()));
+            Ok(())
+        });
+        if let Err(err) = parsed {
+            combine(&mut result, err);
+        }
+        false
+    });
+    result.map(|()| policy)
+}
+
+fn combine(result: &mut syn::Result<()>, err: syn::Error) {
+    match result {
+        Ok(()) => *result = Err(err),
+        Err(prev) => prev.combine(err),
+    }
+}
+
+/// The value types that have flexible deserializers, named as in the function names.
+#[derive(Clone, Copy, PartialEq)]
+enum Base {
+    Bool,
+    Char,
+    F64,
+    I64,
+    U64,
+    String,
+    CowStr,
+    /// The other number types, read with `as_num`.
+    Num(&'static str),
+}
+
+impl Base {
+    fn of(ty: &Type) -> Option<Base> {
+        match last_segment(ty)?.as_str() {
+            "bool" => Some(Base::Bool),
+            "char" => Some(Base::Char),
+            "f64" => Some(Base::F64),
+            "i64" => Some(Base::I64),
+            "u64" => Some(Base::U64),
+            "String" => Some(Base::String),
+            "Cow" if type_arg(ty).and_then(last_segment).as_deref() == Some("str") => Some(Base::CowStr),
+            "u8" => Some(Base::Num("u8")),
+            "u16" => Some(Base::Num("u16")),
+            "u32" => Some(Base::Num("u32")),
+            "usize" => Some(Base::Num("usize")),
+            "i8" => Some(Base::Num("i8")),
+            "i16" => Some(Base::Num("i16")),
+            "i32" => Some(Base::Num("i32")),
+            "isize" => Some(Base::Num("isize")),
+            "f32" => Some(Base::Num("f32")),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Base::Bool => "bool",
+            Base::Char => "char",
+            Base::F64 => "f64",
+            Base::I64 => "i64",
+            Base::U64 => "u64",
+            Base::String => "string",
+            Base::CowStr => "cow_str",
+            Base::Num(_) => "num",
+        }
+    }
+}
+
+enum Shape {
+    Plain(Base),
+    Option(Base),
+    Patch(Base),
+}
+
+impl Shape {
+    fn of(ty: &Type) -> Option<Shape> {
+        match last_segment(ty)?.as_str() {
+            "Option" => Base::of(type_arg(ty)?).map(Shape::Option),
+            "Patch" => Base::of(type_arg(ty)?).map(Shape::Patch),
+            _ => Base::of(ty).map(Shape::Plain),
+        }
+    }
+}
+
+/// The `deserialize_with` path and whether the field needs `#[serde(default)]`.
+fn select(policy: &Policy, ty: &Type) -> Option<(Option<String>, bool)> {
+    use Base::*;
+
+    let shape = Shape::of(ty)?;
+    let function = match (policy, shape) {
+        (Policy::Infer, Shape::Plain(Num(ty))) => return Some((Some(num_path("as_num", ty)), false)),
+        (Policy::Infer, Shape::Option(Num(ty))) => return Some((Some(num_path("as_num_opt", ty)), true)),
+        (_, Shape::Plain(Num(_)) | Shape::Option(Num(_)) | Shape::Patch(Num(_))) => return None,
+        (Policy::Infer, Shape::Plain(base)) => return Some((Some(path(base.name())), false)),
+        (Policy::Infer, Shape::Option(base)) => format!("as_{}_opt", base.name()),
+        (Policy::Infer, Shape::Patch(base)) if base != CowStr => format!("as_{}_patch", base.name()),
+        (Policy::Lenient, Shape::Option(base)) if matches!(base, Bool | Char | F64 | I64 | U64) => format!("as_{}_lenient", base.name()),
+        (Policy::OrDefault, Shape::Plain(base)) if matches!(base, Bool | F64 | I64 | U64) => format!("as_{}_or_default", base.name()),
+        (Policy::Sentinels(sentinels), Shape::Option(base)) => {
+            format!("as_{}_opt_with::<{}, _>", base.name(), quote!(#sentinels))
+        }
+        (Policy::NonEmpty, Shape::Option(String)) => "as_string_opt_non_empty".to_string(),
+        (Policy::NonBlank, Shape::Option(String)) => "as_string_opt_non_blank".to_string(),
+        (Policy::Sentinel, Shape::Option(String)) => "as_string_opt_sentinel".to_string(),
+        (Policy::Lossy, Shape::Plain(String)) => return Some((Some(path("string_lossy")), false)),
+        _ => return None,
+    };
+    Some((Some(format!("serde_flexible::{}", function)), true))
+}
+
+fn path(name: &str) -> String {
+    format!("serde_flexible::as_{}", name)
+}
+
+fn num_path(function: &str, ty: &str) -> String {
+    format!("serde_flexible::{}::<{}, serde_flexible::config::GlobalConfig, _>", function, ty)
+}
+
+/// Top-level keys of all `#[serde(...)]` attributes, e.g. `["default", "deserialize_with"]`.
+fn serde_keys(attrs: &[Attribute]) -> Vec<String> {
+    let mut keys = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        let Ok(list) = attr.meta.require_list() else { continue };
+        let mut expect_key = true;
+        for tt in list.tokens.clone() {
+            match tt {
+                TokenTree::Ident(ident) if expect_key => {
+                    keys.push(ident.to_string());
+                    expect_key = false;
+                }
+                TokenTree::Punct(p) if p.as_char() == ',' => expect_key = true,
+                _ => expect_key = false,
+            }
+        }
+    }
+    keys
+}
+
+/// Whether the field is a `Cow<'a, str>` or `Option<Cow<'a, str>>` with a lifetime serde can
+/// borrow from. Serde only borrows `Cow` fields that are marked with `#[serde(borrow)]`.
+fn borrows(ty: &Type) -> bool {
+    let cow = match Shape::of(ty) {
+        Some(Shape::Plain(Base::CowStr)) => ty,
+        Some(Shape::Option(Base::CowStr)) => match type_arg(ty) {
+            Some(cow) => cow,
+            None => return false,
+        },
+        _ => return false,
+    };
+    generic_args(cow).is_some_and(|args| {
+        args.iter().any(|arg| matches!(arg, GenericArgument::Lifetime(lt) if lt.ident != "static"))
+    })
+}
+
+fn is_optional(ty: &Type) -> bool {
+    last_segment(ty).is_some_and(|ident| ident == "Option" || ident == "Patch")
+}
+
+fn last_segment(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(p) if p.qself.is_none() => p.path.segments.last().map(|s| s.ident.to_string()),
+        Type::Group(g) => last_segment(&g.elem),
+        Type::Paren(p) => last_segment(&p.elem),
+        _ => None,
+    }
+}
+
+/// The last type argument of the last path segment, e.g. `T` in `Option<T>` or `str` in `Cow<'a, str>`.
+fn type_arg(ty: &Type) -> Option<&Type> {
+    generic_args(ty)?.iter().rev().find_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+}
+
+/// The angle bracketed arguments of the last path segment, e.g. `'a, str` in `Cow<'a, str>`.
+fn generic_args(ty: &Type) -> Option<&Punctuated<GenericArgument, Token![,]>> {
+    match ty {
+        Type::Path(p) if p.qself.is_none() => match &p.path.segments.last()?.arguments {
+            PathArguments::AngleBracketed(args) => Some(&args.args),
+            _ => None,
+        },
+        Type::Group(g) => generic_args(&g.elem),
+        Type::Paren(p) => generic_args(&p.elem),
+        _ => None,
+    }
+}
@@ -0,0 +1,172 @@
+use serde::de::{Error, Expected, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_flexible::config::{FlexConfig, GlobalConfig, TypeHints};
+use serde_flexible::{as_flexible, as_flexible_opt, as_num, as_num_opt, as_u64, as_u64_opt, with_config, Flexible, FlexibleParse, Shape, Shaped};
+use std::borrow::Cow;
+use std::fmt;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct Record<'a> {
+    #[serde(with = "serde_flexible::u64")]
+    id: u64,
+    #[serde(with = "serde_flexible::u64::string")]
+    code: u64,
+    #[serde(with = "serde_flexible::i64::opt")]
+    delta: Option<i64>,
+    #[serde(with = "serde_flexible::i64::opt::string")]
+    balance: Option<i64>,
+    #[serde(with = "serde_flexible::bool")]
+    active: bool,
+    #[serde(with = "serde_flexible::f64::lenient")]
+    ratio: Option<f64>,
+    #[serde(with = "serde_flexible::char")]
+    grade: char,
+    #[serde(with = "serde_flexible::string")]
+    name: String,
+    #[serde(with = "serde_flexible::string::opt_sentinel")]
+    note: Option<String>,
+    #[serde(borrow, with = "serde_flexible::cow_str")]
+    label: Cow<'a, str>,
+    #[serde(deserialize_with = "as_num::<u8, GlobalConfig, _>")]
+    level: u8,
+    #[serde(deserialize_with = "as_num_opt::<i16, GlobalConfig, _>")]
+    offset: Option<i16>,
+    #[serde(deserialize_with = "as_num::<f32, GlobalConfig, _>")]
+    weight: f32,
+    tags: Vec<Flexible<u64>>,
+}
+
+/// Runs `f` with type hints, which formats that are not self-describing need.
+fn always<R>(f: impl FnOnce() -> R) -> R {
+    with_config(FlexConfig::new().type_hints(TypeHints::Always), f)
+}
+
+fn ingested() -> Record<'static> {
+    serde_json::from_str(r#"{
+        "id": "1", "code": 2.0, "delta": "none", "balance": "-3", "active": "yes", "ratio": "oops",
+        "grade": 65, "name": 42, "note": "unknown", "label": "x", "level": "200", "offset": -7.0,
+        "weight": "0.5", "tags": ["4", 5]
+    }"#).unwrap()
+}
+
+#[test]
+fn test_json_ingestion() {
+    let record = ingested();
+    assert_eq!((record.id, record.code, record.delta, record.balance), (1, 2, None, Some(-3)));
+    assert_eq!((record.active, record.ratio, record.grade), (true, None, 'A'));
+    assert_eq!((record.name.as_str(), record.note, record.level, record.offset), ("42", None, 200, Some(-7)));
+}
+
+#[test]
+fn test_bincode() {
+    let record = ingested();
+    let bytes = bincode::serialize(&record).unwrap();
+    assert_eq!(always(|| bincode::deserialize::<Record>(&bytes)).unwrap(), record);
+
+    // without type hints bincode can't read the values
+    assert!(bincode::deserialize::<Record>(&bytes).is_err());
+}
+
+#[test]
+fn test_postcard() {
+    let record = ingested();
+    let bytes = postcard::to_allocvec(&record).unwrap();
+    assert_eq!(always(|| postcard::from_bytes::<Record>(&bytes)).unwrap(), record);
+    assert!(postcard::from_bytes::<Record>(&bytes).is_err());
+}
+
+#[test]
+fn test_message_pack() {
+    // self-describing, so the default configuration reads it with `deserialize_any`
+    let record = ingested();
+    let bytes = rmp_serde::to_vec(&record).unwrap();
+    assert_eq!(rmp_serde::from_slice::<Record>(&bytes).unwrap(), record);
+    assert_eq!(always(|| rmp_serde::from_slice::<Record>(&bytes)).unwrap(), record);
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Coerced {
+    #[serde(deserialize_with = "as_u64")]
+    bin: u64,
+    #[serde(deserialize_with = "as_u64")]
+    str: u64,
+    #[serde(deserialize_with = "as_u64_opt")]
+    sentinel: Option<u64>,
+    #[serde(with = "serde_flexible::u64::string")]
+    string: u64,
+}
+
+#[test]
+fn test_message_pack_coercion() {
+    // {"bin": bin "42", "str": "43", "sentinel": "none", "string": 44}
+    let bytes = [
+        &[0x84, 0xa3][..], b"bin", &[0xc4, 0x02], b"42",
+        &[0xa3], b"str", &[0xa2], b"43",
+        &[0xa8], b"sentinel", &[0xa4], b"none",
+        &[0xa6], b"string", &[0x2c],
+    ].concat();
+    assert_eq!(
+        rmp_serde::from_slice::<Coerced>(&bytes).unwrap(),
+        Coerced { bin: 42, str: 43, sentinel: None, string: 44 }
+    );
+}
+
+#[test]
+fn test_shaped() {
+    let values = vec![Shaped::new(1, Shape::Native), Shaped::new(2, Shape::String)];
+    let bytes = rmp_serde::to_vec(&values).unwrap();
+    assert_eq!(rmp_serde::from_slice::<Vec<Shaped<u64>>>(&bytes).unwrap(), values);
+
+    // a string shape is written as a string, which type hints read as a number
+    let bytes = bincode::serialize(&values).unwrap();
+    assert!(!matches!(always(|| bincode::deserialize::<Vec<Shaped<u64>>>(&bytes)), Ok(v) if v == values));
+    let bytes = bincode::serialize(&values[..1]).unwrap();
+    assert_eq!(always(|| bincode::deserialize::<Vec<Shaped<u64>>>(&bytes)).unwrap(), values[..1]);
+}
+
+#[test]
+fn test_json_unchanged() {
+    let record = ingested();
+    let json = serde_json::to_string(&record).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!((&value["code"], &value["balance"]), (&"2".into(), &"-3".into()));
+    assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+    assert_eq!(always(|| serde_json::to_string(&record)).unwrap(), json);
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct AccountId(u64);
+
+impl FlexibleParse for AccountId {
+    fn expecting(formatter: &mut fmt::Formatter<'_>, _: &FlexConfig) -> fmt::Result {
+        formatter.write_str("an account id")
+    }
+
+    fn from_u64<E: Error>(v: u64, _: &FlexConfig, _: &dyn Expected) -> Result<Self, E> {
+        Ok(AccountId(v))
+    }
+
+    fn deserialize_hinted<'de, D: Deserializer<'de>, V: Visitor<'de>>(deserializer: D, visitor: V) -> Result<V::Value, D::Error> {
+        // bincode and postcard write newtype structs as their content
+        deserializer.deserialize_u64(visitor)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct Account {
+    #[serde(deserialize_with = "as_flexible")]
+    id: AccountId,
+    #[serde(deserialize_with = "as_flexible_opt")]
+    parent: Option<AccountId>,
+}
+
+#[test]
+fn test_flexible_parse() {
+    let account: Account = serde_json::from_str(r#"{"id": "1", "parent": "none"}"#).unwrap();
+    let bytes = bincode::serialize(&account).unwrap();
+    assert_eq!(always(|| bincode::deserialize::<Account>(&bytes)).unwrap(), account);
+
+    let account = Account { id: AccountId(1), parent: Some(AccountId(2)) };
+    let bytes = postcard::to_allocvec(&account).unwrap();
+    assert_eq!(always(|| postcard::from_bytes::<Account>(&bytes)).unwrap(), account);
+}
@@ -0,0 +1,38 @@
+use serde::Deserialize;
+use serde_flexible::lenient::{set_discard_handler, Discarded, Raw};
+use serde_flexible::{as_f64_lenient, as_u64_lenient};
+use std::sync::Mutex;
+
+static LOG: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn log(discarded: &Discarded) {
+    LOG.lock().unwrap().push(discarded.to_string());
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Record {
+    #[serde(deserialize_with = "as_f64_lenient")]
+    price: Option<f64>,
+    #[serde(deserialize_with = "as_u64_lenient")]
+    count: Option<u64>,
+}
+
+// the handler is process wide, so everything that touches it lives in this single test
+#[test]
+fn test_discard_handler() {
+    set_discard_handler(Some(log));
+
+    let record = serde_json::from_str::<Record>(r#"{"price": "12,5", "count": -3}"#).unwrap();
+    assert_eq!(record, Record { price: None, count: None });
+    assert_eq!(
+        *LOG.lock().unwrap(),
+        vec![
+            format!("discarded {}, expected null, a float, an integer, or a string", Raw::Str("12,5".to_string())),
+            format!("discarded {}, expected null, an unsigned integer, or a string", Raw::Signed(-3)),
+        ]
+    );
+
+    set_discard_handler(None);
+    serde_json::from_str::<Record>(r#"{"price": "x", "count": "y"}"#).unwrap();
+    assert_eq!(LOG.lock().unwrap().len(), 2);
+}
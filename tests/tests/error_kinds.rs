@@ -0,0 +1,157 @@
+use serde_flexible::config::{Config, DefaultConfig, FlexConfig, Rounding, Strict};
+use serde_flexible::error::{ErrorKind, ErrorKind::*, FlexError};
+use serde_flexible::{as_bool_config, as_char_config, as_f64_config, as_i64_config, as_num, as_u64_config};
+use serde_flexible::{as_bool_opt_config, as_char_opt_config, as_f64_opt_config, as_i64_opt_config, as_u64_opt_config};
+use serde_json::Value;
+
+type Parser = fn(Value) -> Result<(), serde_json::Error>;
+
+/// The kind of the error of `parser` for `json`, `None` if it succeeds.
+fn kind(parser: Parser, json: &str) -> Option<ErrorKind> {
+    let value: Value = serde_json::from_str(json).unwrap();
+    parser(value).err().map(|err| ErrorKind::from_message(&err.to_string()))
+}
+
+fn check<const N: usize>(names: [&str; N], parsers: [Parser; N], matrix: &[(&str, [Option<ErrorKind>; N])]) {
+    for (json, kinds) in matrix {
+        for ((name, parser), expected) in names.iter().zip(parsers).zip(kinds) {
+            assert_eq!(kind(parser, json), *expected, "{name} for {json}");
+        }
+    }
+}
+
+const OK: Option<ErrorKind> = None;
+const IF: Option<ErrorKind> = Some(InvalidFormat);
+const OR: Option<ErrorKind> = Some(OutOfRange);
+const UT: Option<ErrorKind> = Some(UnexpectedType);
+const NN: Option<ErrorKind> = Some(NullNotAllowed);
+const PL: Option<ErrorKind> = Some(PrecisionLoss);
+
+#[test]
+fn test_base_matrix() {
+    check(
+        ["u64", "i64", "f64", "bool", "char"],
+        [
+            |v| as_u64_config::<DefaultConfig, _>(v).map(drop),
+            |v| as_i64_config::<DefaultConfig, _>(v).map(drop),
+            |v| as_f64_config::<DefaultConfig, _>(v).map(drop),
+            |v| as_bool_config::<DefaultConfig, _>(v).map(drop),
+            |v| as_char_config::<DefaultConfig, _>(v).map(drop),
+        ],
+        &[
+            //                              u64 i64 f64 bool char
+            (r#""42""#,                    [OK, OK, OK, IF, IF]),
+            (r#""hello""#,                 [IF, IF, IF, IF, IF]),
+            (r#""123a""#,                  [IF, IF, IF, IF, IF]),
+            (r#""1.2.3""#,                 [IF, IF, IF, IF, IF]),
+            (r#""""#,                      [IF, IF, IF, IF, IF]),
+            (r#""none""#,                  [IF, IF, IF, IF, IF]),
+            (r#""-1""#,                    [OR, OK, OK, IF, IF]),
+            (r#""-0""#,                    [OK, OK, OK, IF, IF]),
+            (r#""18446744073709551616""#,  [OR, OR, OK, IF, IF]),
+            (r#""1.5""#,                   [OK, OK, OK, IF, IF]),
+            (r#""1e400""#,                 [OR, OR, OK, IF, IF]),
+            (r#""NaN""#,                   [IF, IF, OK, IF, IF]),
+            (r#""x""#,                     [IF, IF, IF, IF, OK]),
+            ("-1",                         [OR, OK, OK, IF, OR]),
+            ("2",                          [OK, OK, OK, IF, OK]),
+            ("1.5",                        [OK, OK, OK, UT, UT]),
+            ("1e300",                      [OR, OR, OK, UT, UT]),
+            ("55296",                      [OK, OK, OK, IF, IF]),
+            ("1114112",                    [OK, OK, OK, IF, OR]),
+            ("true",                       [UT, UT, UT, OK, UT]),
+            ("null",                       [NN, NN, NN, NN, NN]),
+            ("[]",                         [UT, UT, UT, UT, UT]),
+            ("{}",                         [UT, UT, UT, UT, UT]),
+        ],
+    );
+}
+
+#[test]
+fn test_opt_matrix() {
+    check(
+        ["u64", "i64", "f64", "bool", "char"],
+        [
+            |v| as_u64_opt_config::<DefaultConfig, _>(v).map(drop),
+            |v| as_i64_opt_config::<DefaultConfig, _>(v).map(drop),
+            |v| as_f64_opt_config::<DefaultConfig, _>(v).map(drop),
+            |v| as_bool_opt_config::<DefaultConfig, _>(v).map(drop),
+            |v| as_char_opt_config::<DefaultConfig, _>(v).map(drop),
+        ],
+        &[
+            //                              u64 i64 f64 bool char
+            (r#""hello""#,                 [IF, IF, IF, IF, IF]),
+            (r#""123a""#,                  [IF, IF, IF, IF, IF]),
+            (r#""-1""#,                    [OR, OK, OK, IF, IF]),
+            (r#""18446744073709551616""#,  [OR, OR, OK, IF, IF]),
+            (r#""none""#,                  [OK, OK, OK, OK, OK]),
+            ("-1",                         [OR, OK, OK, IF, OR]),
+            ("null",                       [OK, OK, OK, OK, OK]),
+            ("[]",                         [UT, UT, UT, UT, UT]),
+        ],
+    );
+}
+
+struct Exact;
+
+impl Config for Exact {
+    fn config() -> FlexConfig { FlexConfig::new().rounding(Rounding::Reject) }
+}
+
+struct NoFloats;
+
+impl Config for NoFloats {
+    fn config() -> FlexConfig { FlexConfig::new().accept_floats(false) }
+}
+
+#[test]
+fn test_config_matrix() {
+    check(
+        ["strict", "exact", "no floats", "u8"],
+        [
+            |v| as_u64_config::<Strict, _>(v).map(drop),
+            |v| as_u64_config::<Exact, _>(v).map(drop),
+            |v| as_u64_config::<NoFloats, _>(v).map(drop),
+            |v| as_num::<u8, DefaultConfig, _>(v).map(drop),
+        ],
+        &[
+            //              strict exact no floats u8
+            (r#""1""#,     [UT, OK, OK, OK]),
+            (r#""x""#,     [UT, IF, IF, IF]),
+            (r#""1.5""#,   [UT, PL, UT, OK]),
+            (r#""256""#,   [UT, OK, OK, OR]),
+            ("1.0",        [UT, OK, UT, OK]),
+            ("1.5",        [UT, PL, UT, OK]),
+            ("-1",         [OR, OR, OR, OR]),
+            ("256",        [OK, OK, OK, OR]),
+        ],
+    );
+}
+
+#[test]
+fn test_flex_error_parts() {
+    let err = serde_json::from_str::<Value>(r#""123a""#).map(as_u64_config::<DefaultConfig, _>).unwrap().unwrap_err();
+    let err = FlexError::from_message(&err.to_string());
+    assert_eq!((err.kind(), err.code(), err.input()), (InvalidFormat, "invalid_format", r#"string "123a""#));
+    assert_eq!(err.expected(), "an unsigned integer or a string");
+}
+
+#[test]
+fn test_plain_serde_errors_are_other() {
+    #[derive(Debug, serde::Deserialize)]
+    struct Row {
+        #[serde(deserialize_with = "serde_flexible::as_u64")]
+        #[allow(dead_code)]
+        flexible: u64,
+        #[allow(dead_code)]
+        plain: u64,
+    }
+
+    let err = serde_json::from_str::<Row>(r#"{"flexible": "x", "plain": 1}"#).unwrap_err().to_string();
+    assert_eq!(ErrorKind::from_message(&err), InvalidFormat, "{err}");
+    assert!(err.starts_with("[invalid_format] invalid value: "), "{err}");
+
+    let err = serde_json::from_str::<Row>(r#"{"flexible": 1, "plain": "x"}"#).unwrap_err().to_string();
+    assert!(err.starts_with(r#"invalid type: string "x", expected u64"#), "{err}");
+    assert_eq!(ErrorKind::from_message(&err), Other);
+}
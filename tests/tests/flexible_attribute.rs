@@ -0,0 +1,147 @@
+use serde::Deserialize;
+use serde_flexible::patch::Patch;
+use serde_flexible::sentinels::NullSentinels;
+use serde_flexible::{as_bool_opt, as_f64_opt, as_string, as_u64_opt, as_u64_patch, flexible};
+use std::borrow::Cow;
+
+fn default_limit() -> Option<u64> { Some(10) }
+
+#[flexible]
+#[derive(Debug, Deserialize, PartialEq)]
+struct Message {
+    #[serde(deserialize_with = "as_string")]
+    owner: String,
+
+    #[serde(deserialize_with = "as_f64_opt")]
+    price: Option<f64>,
+
+    #[serde(rename = "is_active", deserialize_with = "as_bool_opt")]
+    active: Option<bool>,
+
+    #[serde(default = "default_limit", deserialize_with = "as_u64_opt")]
+    limit: Option<u64>,
+
+    #[serde(deserialize_with = "as_u64_patch")]
+    age: Patch<u64>,
+
+    #[flexible(skip)]
+    note: Option<String>,
+}
+
+#[flexible]
+#[derive(Debug, Deserialize, PartialEq)]
+struct Tuple(#[serde(deserialize_with = "as_string")] String, #[serde(deserialize_with = "as_u64_opt")] Option<u64>);
+
+#[flexible]
+#[derive(Debug, Deserialize, PartialEq)]
+enum Event {
+    Click {
+        #[serde(deserialize_with = "as_u64_opt")]
+        x: Option<u64>,
+    },
+}
+
+#[test]
+fn test_missing_optional_fields() {
+    assert_eq!(
+        serde_json::from_str::<Message>(r#"{"owner": 42}"#).unwrap(),
+        Message { owner: "42".to_string(), price: None, active: None, limit: Some(10), age: Patch::Missing, note: None }
+    );
+    assert_eq!(
+        serde_json::from_str::<Message>(r#"{"owner": "a", "price": "1.5", "is_active": "yes", "limit": null, "age": "null"}"#).unwrap(),
+        Message { owner: "a".to_string(), price: Some(1.5), active: Some(true), limit: None, age: Patch::Null, note: None }
+    );
+
+    // required fields stay required
+    assert!(serde_json::from_str::<Message>(r#"{"price": 1}"#).is_err());
+
+    assert_eq!(serde_json::from_str::<Tuple>(r#"["a"]"#).unwrap(), Tuple("a".to_string(), None));
+    assert_eq!(serde_json::from_str::<Event>(r#"{"Click": {}}"#).unwrap(), Event::Click { x: None });
+}
+
+struct Dash;
+
+impl NullSentinels for Dash {
+    fn sentinels() -> &'static [&'static str] { &["-"] }
+}
+
+#[flexible]
+#[derive(Debug, Deserialize, PartialEq)]
+struct Inferred<'a> {
+    id: u64,
+    delta: i64,
+    price: f64,
+    active: bool,
+    letter: char,
+    name: String,
+    code: Cow<'a, str>,
+    #[flexible(sentinels = Dash)]
+    alias: Option<Cow<'a, str>>,
+    count: Option<u64>,
+    flag: Option<bool>,
+    label: Option<String>,
+    age: Patch<u64>,
+    #[flexible(lenient)]
+    rating: Option<f64>,
+    #[flexible(or_default)]
+    retries: u64,
+    #[flexible(sentinels = Dash)]
+    score: Option<i64>,
+    #[flexible(non_blank)]
+    comment: Option<String>,
+    #[flexible(lossy)]
+    raw: String,
+    #[flexible(skip)]
+    strict: u64,
+    #[serde(rename = "other")]
+    unsupported: Vec<u64>,
+}
+
+#[flexible]
+#[derive(Debug, Deserialize, PartialEq)]
+struct Narrow {
+    port: u16,
+    level: i8,
+    ratio: f32,
+    size: usize,
+    retries: Option<u8>,
+    offset: Option<i32>,
+}
+
+#[test]
+fn test_narrow_numbers() {
+    assert_eq!(
+        serde_json::from_str::<Narrow>(r#"{"port": "8080", "level": -3.0, "ratio": "0.5", "size": 7, "offset": "none"}"#).unwrap(),
+        Narrow { port: 8080, level: -3, ratio: 0.5, size: 7, retries: None, offset: None }
+    );
+    assert!(serde_json::from_str::<Narrow>(r#"{"port": 70000, "level": 0, "ratio": 0, "size": 0}"#).is_err());
+    assert!(serde_json::from_str::<Narrow>(r#"{"port": 1, "level": 0, "ratio": 0, "size": 0, "retries": "300"}"#).is_err());
+}
+
+#[test]
+fn test_inferred_deserializers() {
+    let v: Inferred = serde_json::from_str(r#"{
+        "id": "1", "delta": "-2", "price": "2.5", "active": "yes", "letter": 66, "name": 3,
+        "code": "c", "alias": "-", "count": "none", "flag": "off", "rating": "bad", "retries": "x",
+        "score": "-", "comment": "  ", "raw": "hi", "strict": 4, "other": [5]
+    }"#).unwrap();
+    assert_eq!(
+        v,
+        Inferred {
+            id: 1, delta: -2, price: 2.5, active: true, letter: 'B', name: "3".to_string(), code: Cow::Borrowed("c"),
+            alias: None, count: None, flag: Some(false), label: None, age: Patch::Missing, rating: None, retries: 0, score: None,
+            comment: None, raw: "hi".to_string(), strict: 4, unsupported: vec![5],
+        }
+    );
+    assert!(matches!(v.code, Cow::Borrowed(_)));
+
+    let v: Inferred = serde_json::from_str(r#"{
+        "id": 1, "delta": 1, "price": 1, "active": true, "letter": "a", "name": "", "code": "",
+        "alias": "a", "raw": "", "strict": 4, "other": []
+    }"#).unwrap();
+    assert!(matches!(v.alias, Some(Cow::Borrowed("a"))));
+
+    // skipped and unsupported fields keep serde's own rules
+    assert!(serde_json::from_str::<Inferred>(r#"{"id": 1, "delta": 1, "price": 1, "active": true, "letter": "a", "name": "", "code": "", "raw": "", "strict": "4", "other": []}"#).is_err());
+    assert!(serde_json::from_str::<Inferred>(r#"{"id": 1, "delta": 1, "price": 1, "active": true, "letter": "a", "name": "", "code": "", "raw": "", "strict": 4, "other": ["5"]}"#).is_err());
+}
@@ -0,0 +1,49 @@
+use serde::Deserialize;
+use serde_flexible::config::{config, set_config, FlexConfig, Rounding};
+use serde_flexible::{as_bool, as_string_opt_sentinel, as_u64, as_u64_lenient, as_u64_opt, Flexible};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Row {
+    #[serde(deserialize_with = "as_u64")]
+    count: u64,
+    #[serde(deserialize_with = "as_u64_opt")]
+    limit: Option<u64>,
+    #[serde(deserialize_with = "as_u64_lenient")]
+    score: Option<u64>,
+    #[serde(deserialize_with = "as_bool")]
+    active: bool,
+    #[serde(deserialize_with = "as_string_opt_sentinel")]
+    comment: Option<String>,
+}
+
+fn parse(json: &str) -> Result<Row, serde_json::Error> {
+    serde_json::from_str(json)
+}
+
+// the global configuration is process wide, so everything that touches it lives in this single test
+#[test]
+fn test_global_config() {
+    assert_eq!(config(), FlexConfig::DEFAULT);
+    let json = r#"{"count": "2.5", "limit": "-", "score": 1.5, "active": "si", "comment": "-"}"#;
+    assert!(parse(json).is_err());
+
+    set_config(
+        FlexConfig::new()
+            .rounding(Rounding::Floor)
+            .null_sentinels(&["-"])
+            .bool_words(&["si"], &["no"]),
+    );
+    assert_eq!(
+        parse(json).unwrap(),
+        Row { count: 2, limit: None, score: Some(1), active: true, comment: None }
+    );
+    assert_eq!(serde_json::from_str::<Vec<Flexible<u64>>>(r#"[1.9, "2.9"]"#).unwrap(), [Flexible(1), Flexible(2)]);
+
+    set_config(FlexConfig::new().strict(true));
+    let row = parse(r#"{"count": 2, "limit": "none", "score": "3", "active": true, "comment": "x"}"#).unwrap();
+    assert_eq!((row.limit, row.score), (None, None));
+    assert!(parse(r#"{"count": "2", "limit": null, "score": null, "active": true, "comment": null}"#).is_err());
+
+    set_config(FlexConfig::DEFAULT);
+    assert_eq!(parse(r#"{"count": "2.5", "limit": "none", "score": 1.5, "active": "yes", "comment": "none"}"#).unwrap().count, 3);
+}
@@ -0,0 +1,37 @@
+use serde::Deserialize;
+use serde_flexible::config::{Config, FlexConfig, TypeHints};
+use serde_flexible::{as_bool_config, as_num, as_num_opt};
+
+/// serde-json-core can't `deserialize_any`, so every value is read as the field type.
+struct Hinted;
+
+impl Config for Hinted {
+    fn config() -> FlexConfig { FlexConfig::new().type_hints(TypeHints::Always) }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Settings {
+    #[serde(deserialize_with = "as_bool_config::<Hinted, _>")]
+    enabled: bool,
+    #[serde(deserialize_with = "as_num::<u16, Hinted, _>")]
+    port: u16,
+    #[serde(deserialize_with = "as_num_opt::<i8, Hinted, _>")]
+    offset: Option<i8>,
+    #[serde(deserialize_with = "as_num::<f32, Hinted, _>")]
+    gain: f32,
+}
+
+#[test]
+fn test_json_core() {
+    let json = r#"{"enabled": true, "port": 8080, "offset": null, "gain": 0.5}"#;
+    let (settings, _) = serde_json_core::from_str::<Settings>(json).unwrap();
+    assert_eq!(settings, Settings { enabled: true, port: 8080, offset: None, gain: 0.5 });
+
+    let json = r#"{"enabled": true, "port": 70000, "offset": -1, "gain": 0.5}"#;
+    assert!(serde_json_core::from_str::<Settings>(json).is_err());
+
+    #[derive(Debug, Deserialize)]
+    struct Port(#[serde(deserialize_with = "as_num::<u16, serde_flexible::config::Strict, _>")] u16);
+    let port = serde_json_core::from_str::<Port>("443").map(|(port, _)| port.0);
+    assert_eq!(port, Err(serde_json_core::de::Error::AnyIsUnsupported));
+}
@@ -0,0 +1,46 @@
+use serde::Deserialize;
+use serde_flexible::sentinels::{null_sentinels, set_null_sentinels, DEFAULT_NULL_SENTINELS};
+use serde_flexible::{as_bool_opt, as_f64_opt, as_string_opt, as_string_opt_sentinel, as_u64_opt};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Row {
+    #[serde(deserialize_with = "as_u64_opt")]
+    count: Option<u64>,
+    #[serde(deserialize_with = "as_f64_opt")]
+    price: Option<f64>,
+    #[serde(deserialize_with = "as_bool_opt")]
+    active: Option<bool>,
+    #[serde(deserialize_with = "as_string_opt_sentinel")]
+    comment: Option<String>,
+    #[serde(deserialize_with = "as_string_opt")]
+    raw: Option<String>,
+}
+
+fn parse(v: &str) -> Result<Row, serde_json::Error> {
+    serde_json::from_str(&format!(r#"{{"count": {v}, "price": {v}, "active": {v}, "comment": {v}, "raw": {v}}}"#))
+}
+
+// the global set is process wide, so everything that touches it lives in this single test
+#[test]
+fn test_global_sentinels() {
+    assert_eq!(null_sentinels(), DEFAULT_NULL_SENTINELS);
+    assert!(parse(r#""unknown""#).unwrap().count.is_none());
+    assert!(parse(r#""N/A""#).is_err());
+
+    set_null_sentinels(&["N/A", "-", "nil", "NULL", "\\N", "#N/A", ""]);
+
+    for v in [r#""N/A""#, r#""n/a""#, r#""-""#, r#""nil""#, r#""null""#, r#""\\N""#, r##""#N/A""##, r#""""#] {
+        let row = parse(v).unwrap();
+        assert_eq!((row.count, row.price, row.active, row.comment), (None, None, None, None), "{v}");
+        // as_string_opt keeps every string as is
+        assert!(row.raw.is_some(), "{v}");
+    }
+
+    // "none" and "unknown" are no longer sentinels
+    assert!(parse(r#""none""#).is_err());
+    let err = parse(r#""abc""#).unwrap_err().to_string();
+    assert!(err.contains(r##"(null sentinels: "N/A", "-", "nil", "NULL", "\\N", "#N/A", "")"##), "{err}");
+
+    set_null_sentinels(DEFAULT_NULL_SENTINELS);
+    assert!(parse(r#""none""#).unwrap().count.is_none());
+}
@@ -15,7 +15,7 @@ fn test_base_good_parse() {
     assert_eq!(
         serde_json::from_str::<Message>(r#"{
             "owner": "8a8cc628-88e3-4550-90c0-a64bd8f446dd",
-            "referral": 7132,
+            "referral": 7132
         }"#).unwrap(),
         Message {
             owner: "8a8cc628-88e3-4550-90c0-a64bd8f446dd".to_string(),
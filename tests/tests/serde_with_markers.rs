@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use serde_flexible::config::{Lenient, Strict};
+use serde_flexible::normalize::{Lowercase, Trim};
+use serde_flexible::patch::Patch;
+use serde_flexible::sentinels::NullSentinels;
+use serde_flexible::{AsBool, AsBoolOpt, AsBoolOr, AsF64Lenient, AsNum, AsNumOpt, AsStringNormalized, AsU64, AsU64OptWith, AsU64Patch};
+use serde_with::serde_as;
+use std::collections::HashMap;
+
+struct Dash;
+
+impl NullSentinels for Dash {
+    fn sentinels() -> &'static [&'static str] { &["-"] }
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+struct Data {
+    #[serde_as(as = "Vec<AsU64>")]
+    ids: Vec<u64>,
+    #[serde_as(as = "HashMap<_, AsBool>")]
+    flags: HashMap<String, bool>,
+    #[serde_as(as = "Option<AsBool>")]
+    maybe: Option<bool>,
+    #[serde_as(as = "Vec<AsBoolOpt>")]
+    states: Vec<Option<bool>>,
+    #[serde_as(as = "(AsU64, AsF64Lenient)")]
+    pair: (u64, Option<f64>),
+    #[serde_as(as = "Vec<AsU64OptWith<Dash>>")]
+    counts: Vec<Option<u64>>,
+    #[serde_as(as = "AsStringNormalized<(Trim, Lowercase)>")]
+    email: String,
+    #[serde_as(as = "AsBoolOr<true>")]
+    visible: bool,
+    #[serde_as(as = "Vec<AsNum<Lenient>>")]
+    ports: Vec<u16>,
+    #[serde_as(as = "Vec<AsNumOpt<Strict>>")]
+    levels: Vec<Option<i8>>,
+    #[serde(default, skip_serializing_if = "Patch::is_missing")]
+    #[serde_as(as = "AsU64Patch")]
+    age: Patch<u64>,
+}
+
+#[test]
+fn test_serde_as() {
+    let data: Data = serde_json::from_str(r#"{
+        "ids": ["1", 2, 3.0],
+        "flags": {"a": "yes", "b": 0},
+        "maybe": "on",
+        "states": [null, "none", "t"],
+        "pair": ["7", "oops"],
+        "counts": ["-", "5"],
+        "email": "  John@Example.COM ",
+        "visible": "maybe",
+        "ports": [" 80 ", 443.0],
+        "levels": [null, -1]
+    }"#).unwrap();
+
+    assert_eq!(data, Data {
+        ids: vec![1, 2, 3],
+        flags: HashMap::from([("a".to_string(), true), ("b".to_string(), false)]),
+        maybe: Some(true),
+        states: vec![None, None, Some(true)],
+        pair: (7, None),
+        counts: vec![None, Some(5)],
+        email: "john@example.com".to_string(),
+        visible: true,
+        ports: vec![80, 443],
+        levels: vec![None, Some(-1)],
+        age: Patch::Missing,
+    });
+
+    let json = serde_json::to_value(&data).unwrap();
+    assert_eq!(json["ids"], serde_json::json!([1, 2, 3]));
+    assert_eq!(json["pair"], serde_json::json!([7, null]));
+    assert_eq!(json.get("age"), None);
+
+    assert!(serde_json::from_str::<Data>(r#"{"ids": ["x"]}"#).is_err());
+}